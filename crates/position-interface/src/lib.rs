@@ -0,0 +1,221 @@
+//! Position-reporting interface between collateral-vault and its integrators.
+//!
+//! An integrator that locks collateral keeps one `PositionSummary` account per
+//! vault at the PDA `[POSITION_SUMMARY_SEED, vault]` under its own program id.
+//! The account starts with `POSITION_SUMMARY_DISCRIMINATOR` followed by the
+//! Borsh-encoded summary. `withdraw` in collateral-vault only accepts summaries
+//! that match this layout, sit at the canonical address and carry a supported
+//! version.
+//!
+//! Integrators that keep positions off-chain can instead have a registered risk
+//! signer sign a `RiskAttestation`. The client places an ed25519 precompile
+//! instruction over `RiskAttestation::message()` in the withdraw transaction
+//! and passes the integrator's registry account where the summary would go.
+
+use anchor_lang::prelude::*;
+
+/// Seed of the per-vault summary PDA: `[POSITION_SUMMARY_SEED, vault]`.
+pub const POSITION_SUMMARY_SEED: &[u8] = b"position_summary";
+
+/// Account discriminator (Anchor's discriminator for an account named `PositionSummary`).
+pub const POSITION_SUMMARY_DISCRIMINATOR: [u8; 8] = [80, 53, 187, 252, 193, 103, 82, 205];
+
+/// Layout version written by integrators implementing this crate.
+pub const POSITION_SUMMARY_VERSION: u8 = 1;
+
+/// Summaries reporting open positions older than this many slots are rejected.
+pub const MAX_SUMMARY_AGE_SLOTS: u64 = 150;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PositionSummary {
+    pub version: u8,
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub open_positions: u64,
+    pub locked_amount: u64,
+    // Collateral the integrator needs the vault to keep for its open positions
+    pub required_margin: u64,
+    pub last_updated_slot: u64,
+    // Bump of the summary PDA, so readers can verify the address cheaply
+    pub bump: u8,
+}
+
+/// Why an account was rejected as a position summary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SummaryError {
+    TooShort,
+    Discriminator,
+    Version,
+    Layout,
+    Address,
+}
+
+impl PositionSummary {
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Decode a summary from full account data, checking the discriminator and version.
+    pub fn decode(data: &[u8]) -> core::result::Result<Self, SummaryError> {
+        if data.len() < Self::SPACE {
+            return Err(SummaryError::TooShort);
+        }
+        if data[..8] != POSITION_SUMMARY_DISCRIMINATOR {
+            return Err(SummaryError::Discriminator);
+        }
+        let summary =
+            Self::deserialize(&mut &data[8..Self::SPACE]).map_err(|_| SummaryError::Layout)?;
+        if summary.version != POSITION_SUMMARY_VERSION {
+            return Err(SummaryError::Version);
+        }
+        Ok(summary)
+    }
+
+    /// Canonical summary address for `vault` under `program_id`.
+    pub fn find_address(program_id: &Pubkey, vault: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[POSITION_SUMMARY_SEED, vault.as_ref()], program_id)
+    }
+
+    /// Check that `address` is this summary's canonical PDA under `program_id`.
+    pub fn verify_address(
+        &self,
+        program_id: &Pubkey,
+        address: &Pubkey,
+    ) -> core::result::Result<(), SummaryError> {
+        let expected = Pubkey::create_program_address(
+            &[POSITION_SUMMARY_SEED, self.vault.as_ref(), &[self.bump]],
+            program_id,
+        )
+        .map_err(|_| SummaryError::Address)?;
+        if expected != *address {
+            return Err(SummaryError::Address);
+        }
+        Ok(())
+    }
+
+    /// True when the summary was written no more than `MAX_SUMMARY_AGE_SLOTS` before `slot`.
+    pub fn is_fresh(&self, slot: u64) -> bool {
+        self.last_updated_slot <= slot && slot - self.last_updated_slot <= MAX_SUMMARY_AGE_SLOTS
+    }
+}
+
+/// Prefix of every signed attestation message.
+pub const RISK_ATTESTATION_DOMAIN: &[u8] = b"collateral-vault/risk-attestation/v1";
+
+/// Off-chain integrator's statement about one vault, signed by its risk signer.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct RiskAttestation {
+    // Integrator program the attestation speaks for
+    pub integrator: Pubkey,
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub open_positions: u64,
+    pub required_margin: u64,
+    // Unix timestamp after which the attestation is void
+    pub expires_at: i64,
+    // Must exceed the last nonce accepted for this vault and integrator
+    pub nonce: u64,
+}
+
+impl RiskAttestation {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8;
+
+    /// Bytes the risk signer signs: the domain prefix followed by the Borsh encoding.
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = RISK_ATTESTATION_DOMAIN.to_vec();
+        message.extend_from_slice(&self.try_to_vec().unwrap_or_default());
+        message
+    }
+
+    /// Decode a signed message; `None` unless it is exactly a domain-prefixed attestation.
+    pub fn from_message(message: &[u8]) -> Option<Self> {
+        let body = message.strip_prefix(RISK_ATTESTATION_DOMAIN)?;
+        if body.len() != Self::LEN {
+            return None;
+        }
+        Self::try_from_slice(body).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(program_id: &Pubkey) -> PositionSummary {
+        let vault = Pubkey::new_unique();
+        let (_, bump) = PositionSummary::find_address(program_id, &vault);
+        PositionSummary {
+            version: POSITION_SUMMARY_VERSION,
+            vault,
+            owner: Pubkey::new_unique(),
+            open_positions: 1,
+            locked_amount: 500,
+            required_margin: 350,
+            last_updated_slot: 1_000,
+            bump,
+        }
+    }
+
+    #[test]
+    fn decode_checks_discriminator_version_and_length() {
+        let summary = sample(&Pubkey::new_unique());
+        let mut data = POSITION_SUMMARY_DISCRIMINATOR.to_vec();
+        data.extend(summary.try_to_vec().unwrap());
+        assert_eq!(data.len(), PositionSummary::SPACE);
+        assert!(PositionSummary::decode(&data) == Ok(summary));
+
+        assert!(PositionSummary::decode(&data[..data.len() - 1]) == Err(SummaryError::TooShort));
+        let mut bad = data.clone();
+        bad[0] ^= 1;
+        assert!(PositionSummary::decode(&bad) == Err(SummaryError::Discriminator));
+        let mut bad = data.clone();
+        bad[8] = POSITION_SUMMARY_VERSION + 1;
+        assert!(PositionSummary::decode(&bad) == Err(SummaryError::Version));
+    }
+
+    #[test]
+    fn address_and_freshness_checks() {
+        let program_id = Pubkey::new_unique();
+        let summary = sample(&program_id);
+        let (address, _) = PositionSummary::find_address(&program_id, &summary.vault);
+        assert!(summary.verify_address(&program_id, &address).is_ok());
+        assert!(summary
+            .verify_address(&program_id, &Pubkey::new_unique())
+            .is_err());
+        assert!(summary
+            .verify_address(&Pubkey::new_unique(), &address)
+            .is_err());
+
+        assert!(summary.is_fresh(1_000));
+        assert!(summary.is_fresh(1_000 + MAX_SUMMARY_AGE_SLOTS));
+        assert!(!summary.is_fresh(1_001 + MAX_SUMMARY_AGE_SLOTS));
+        assert!(!summary.is_fresh(999));
+    }
+
+    #[test]
+    fn attestation_message_round_trips_and_requires_domain() {
+        let attestation = RiskAttestation {
+            integrator: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            open_positions: 3,
+            required_margin: 1_250,
+            expires_at: 1_700_000_000,
+            nonce: 9,
+        };
+        let message = attestation.message();
+        assert_eq!(
+            message.len(),
+            RISK_ATTESTATION_DOMAIN.len() + RiskAttestation::LEN
+        );
+        assert_eq!(RiskAttestation::from_message(&message), Some(attestation));
+
+        assert_eq!(
+            RiskAttestation::from_message(&message[RISK_ATTESTATION_DOMAIN.len()..]),
+            None
+        );
+        assert_eq!(
+            RiskAttestation::from_message(&message[..message.len() - 1]),
+            None
+        );
+    }
+}
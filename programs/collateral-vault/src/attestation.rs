@@ -0,0 +1,170 @@
+//! Off-chain signed messages read from the ed25519 precompile.
+//!
+//! An integrator whose registry record names a `risk_signer` may vouch for a
+//! vault at withdraw time with a signed [`RiskAttestation`] instead of an
+//! on-chain position summary, and a vault's co-signer may approve a large
+//! outflow with a signed [`CoSignerApproval`]. The client adds an ed25519 precompile
+//! instruction to the transaction; the runtime verifies the signature before
+//! the program runs, so the program only has to find the instruction through
+//! the instructions sysvar and read the public key and message from it.
+//!
+//! Ed25519 instruction data layout:
+//!
+//! | Offset      | Size | Field                                        |
+//! |-------------|------|----------------------------------------------|
+//! | 0           | 1    | number of signatures                         |
+//! | 1           | 1    | padding                                      |
+//! | 2 + 14 * i  | 14   | offsets of signature `i` (seven `u16`s)      |
+//!
+//! Each offsets entry is `signature_offset`, `signature_instruction_index`,
+//! `public_key_offset`, `public_key_instruction_index`, `message_data_offset`,
+//! `message_data_size`, `message_instruction_index`. Only entries whose three
+//! instruction indexes are `u16::MAX` (data inside the ed25519 instruction
+//! itself) are accepted.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+
+use crate::error::ErrorCode;
+
+pub use position_interface::RiskAttestation;
+
+/// Native ed25519 signature verification program.
+pub const ED25519_PROGRAM_ID: Pubkey = pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+
+/// `(public_key, message)` for every signature in an ed25519 instruction's data.
+pub fn parse_ed25519_instruction(data: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START,
+        ErrorCode::AttestationInvalid
+    );
+    let count = data[0] as usize;
+    let read_u16 = |at: usize| -> Result<usize> {
+        let bytes = data
+            .get(at..at + 2)
+            .ok_or(error!(ErrorCode::AttestationInvalid))?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+    };
+
+    let mut entries = Vec::with_capacity(count);
+    for i in 0..count {
+        let base = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_LEN;
+        let signature_ix = read_u16(base + 2)?;
+        let pubkey_offset = read_u16(base + 4)?;
+        let pubkey_ix = read_u16(base + 6)?;
+        let message_offset = read_u16(base + 8)?;
+        let message_size = read_u16(base + 10)?;
+        let message_ix = read_u16(base + 12)?;
+        // Key and message must live in this instruction, not be borrowed from another
+        let this_ix = u16::MAX as usize;
+        require!(
+            signature_ix == this_ix && pubkey_ix == this_ix && message_ix == this_ix,
+            ErrorCode::AttestationInvalid
+        );
+
+        let pubkey = data
+            .get(pubkey_offset..pubkey_offset + PUBKEY_LEN)
+            .ok_or(error!(ErrorCode::AttestationInvalid))?;
+        let message = data
+            .get(message_offset..message_offset + message_size)
+            .ok_or(error!(ErrorCode::AttestationInvalid))?;
+        entries.push((
+            Pubkey::try_from(pubkey).map_err(|_| error!(ErrorCode::AttestationInvalid))?,
+            message.to_vec(),
+        ));
+    }
+    Ok(entries)
+}
+
+/// First message signed by `signer` in the transaction's ed25519 instructions
+/// that `pick` accepts.
+fn find_signed_message<T>(
+    instructions: &AccountInfo<'_>,
+    signer: &Pubkey,
+    pick: impl Fn(&[u8]) -> Option<T>,
+) -> Result<Option<T>> {
+    let mut index = 0usize;
+    while let Ok(ix) = sysvar_instructions::load_instruction_at_checked(index, instructions) {
+        index += 1;
+        if ix.program_id != ED25519_PROGRAM_ID {
+            continue;
+        }
+        for (pubkey, message) in parse_ed25519_instruction(&ix.data)? {
+            if pubkey != *signer {
+                continue;
+            }
+            if let Some(found) = pick(&message) {
+                return Ok(Some(found));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Find an attestation signed by `signer` for `integrator` and `vault` among the
+/// transaction's ed25519 instructions. Fails with `AttestationMissing` if none.
+pub fn find_risk_attestation(
+    instructions: &AccountInfo<'_>,
+    signer: &Pubkey,
+    integrator: &Pubkey,
+    vault: &Pubkey,
+) -> Result<RiskAttestation> {
+    find_signed_message(instructions, signer, |message| {
+        RiskAttestation::from_message(message)
+            .filter(|a| a.integrator == *integrator && a.vault == *vault)
+    })?
+    .ok_or(error!(ErrorCode::AttestationMissing))
+}
+
+/// Prefix of every signed co-signer approval message.
+pub const CO_SIGNER_APPROVAL_DOMAIN: &[u8] = b"collateral-vault/co-signer-approval/v1";
+
+/// A vault co-signer's approval of one outflow above the policy threshold.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CoSignerApproval {
+    pub vault: Pubkey,
+    pub amount: u64,
+    // Destination token account (the vault itself for a timelock)
+    pub recipient: Pubkey,
+    // Unix timestamp after which the approval is void
+    pub expires_at: i64,
+    // Must exceed the last nonce accepted for this vault
+    pub nonce: u64,
+}
+
+impl CoSignerApproval {
+    pub const LEN: usize = 32 + 8 + 32 + 8 + 8;
+
+    /// Bytes the co-signer signs: the domain prefix followed by the Borsh encoding.
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = CO_SIGNER_APPROVAL_DOMAIN.to_vec();
+        message.extend_from_slice(&self.try_to_vec().unwrap_or_default());
+        message
+    }
+
+    /// Decode a signed message; `None` unless it is exactly a domain-prefixed approval.
+    pub fn from_message(message: &[u8]) -> Option<Self> {
+        let body = message.strip_prefix(CO_SIGNER_APPROVAL_DOMAIN)?;
+        if body.len() != Self::LEN {
+            return None;
+        }
+        Self::try_from_slice(body).ok()
+    }
+}
+
+/// Find an approval signed by `signer` for `vault` among the transaction's
+/// ed25519 instructions. Fails with `CoSignerRequired` if none.
+pub fn find_co_signer_approval(
+    instructions: &AccountInfo<'_>,
+    signer: &Pubkey,
+    vault: &Pubkey,
+) -> Result<CoSignerApproval> {
+    find_signed_message(instructions, signer, |message| {
+        CoSignerApproval::from_message(message).filter(|a| a.vault == *vault)
+    })?
+    .ok_or(error!(ErrorCode::CoSignerRequired))
+}
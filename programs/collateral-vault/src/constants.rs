@@ -1,7 +1,12 @@
 // PDA seeds
 pub const VAULT_SEED: &[u8] = b"vault";
 pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
-pub const POSITION_SUMMARY_SEED: &[u8] = b"position-summary";
+pub const COLLATERAL_CONFIG_SEED: &[u8] = b"collateral_config";
+pub const YIELD_POSITION_SEED: &[u8] = b"yield_position";
+pub const YIELD_STRATEGY_SEED: &[u8] = b"yield_strategy";
+pub const YIELD_REDEMPTION_SEED: &[u8] = b"yield_redemption";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const INTEGRATOR_SEED: &[u8] = b"integrator";
 
 // Sizing limits
 pub const MAX_AUTHORIZED_PROGRAMS: usize = 64; // conservative upper bound for admin list
@@ -10,8 +15,44 @@ pub const MAX_DELEGATES: usize = 16; // per-vault user delegates allowed to act
 pub const MAX_TIMELOCKS: usize = 64; // max concurrent scheduled timelocks per vault
 pub const MAX_PENDING_WITHDRAWALS: usize = 64; // max pending withdrawal requests per vault
 pub const MAX_WITHDRAW_WHITELIST: usize = 32; // max whitelisted recipient addresses per vault
+pub const MAX_SETTLEMENT_ENTRIES: usize = 32; // max vault entries per batch_settle call
+pub const MAX_RISK_LEVELS: usize = 8; // risk levels (0..MAX_RISK_LEVELS) with configurable parameters
+pub const MAX_PENDING_POLICY_CHANGES: usize = 8; // queued security-policy relaxations per vault
+pub const MAX_GUARDIANS: usize = 10; // recovery guardians per vault
+pub const MAX_VAULT_INTEGRATORS: usize = 8; // integrator programs a vault owner may approve
+pub const MAX_INTEGRATOR_NAME_LEN: usize = 32; // bytes of integrator registry display name
+pub const MAX_ATTESTATION_TTL_SECONDS: i64 = 5 * 60; // longest validity window accepted for a risk attestation
+pub const MAX_LOCK_LEASE_SECONDS: i64 = 90 * 24 * 60 * 60; // longest lease a lock or renewal may request
+pub const LOCK_LEASE_WARNING_SECONDS: i64 = 24 * 60 * 60; // window before expiry in which keepers are alerted
+pub const MAX_CO_SIGNER_APPROVAL_TTL_SECONDS: i64 = 10 * 60; // longest validity window accepted for a co-signer approval
+pub const CO_SIGNER_POLICY_DELAY_SECONDS: i64 = 48 * 60 * 60; // wait before a co-signer policy change takes effect
+pub const PANIC_UNLOCK_COOLDOWN_SECONDS: i64 = 24 * 60 * 60; // wait between requesting and completing a panic unlock
+pub const RECOVERY_DELAY_SECONDS: i64 = 7 * 24 * 60 * 60; // owner's window to veto a guardian recovery
+pub const GUARDIAN_CHANGE_DELAY_SECONDS: i64 = 3 * 24 * 60 * 60; // wait before a new guardian set takes effect
+pub const MIN_INACTIVITY_PERIOD_SECONDS: i64 = 30 * 24 * 60 * 60; // shortest owner silence after which a beneficiary may inherit
+pub const MIN_INTEGRATOR_GRACE_PERIOD_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days to unwind before locks become reclaimable
+
+// Account layout written by this program; version-1 accounts are migrated with migrate_*
+pub const ACCOUNT_LAYOUT_VERSION: u8 = 2;
 
 // Business rules
 // Minimum deposit amount in smallest units (token decimals apply).
 // Set to 1 to effectively mirror > 0, can be raised by code updates if required.
 pub const MIN_DEPOSIT: u64 = 1;
+// Denominator for all basis-point ratios (10_000 = 100%).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// Decimals of USD values returned by collateral valuation (1 USD = 10^6).
+pub const USD_VALUE_DECIMALS: u8 = 6;
+// Oracle publish times may run this far ahead of the cluster clock.
+pub const MAX_ORACLE_CLOCK_SKEW_SECONDS: i64 = 5;
+// Seconds in a (non-leap) year, used to annualize realized yield.
+pub const SECONDS_PER_YEAR: i64 = 31_536_000;
+// Unfilled yield redemption tickets are written off after this long.
+pub const YIELD_REDEMPTION_TIMEOUT_SECONDS: i64 = 14 * 24 * 60 * 60;
+// Upper bounds for governance-set auto-compound fees (bps of the claimed rewards).
+pub const MAX_PERFORMANCE_FEE_BPS: u16 = 2_000;
+pub const MAX_KEEPER_BOUNTY_BPS: u16 = 500;
+// Upper bound for per-operation fees (bps of the amount moved)
+pub const MAX_FEE_BPS: u16 = 1_000;
+// Upper bound for the annual custody fee (bps of balance per year)
+pub const MAX_CUSTODY_FEE_BPS: u16 = 500;
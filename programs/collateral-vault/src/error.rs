@@ -36,4 +36,112 @@ pub enum ErrorCode {
     PositionSummaryMissing,
     #[msg("Invalid position summary supplied")]
     PositionSummaryInvalid,
+    #[msg("Settlement deltas must sum to zero")]
+    UnbalancedSettlement,
+    #[msg("Invalid settlement batch")]
+    InvalidSettlement,
+    #[msg("Invalid risk parameters")]
+    InvalidRiskParams,
+    #[msg("Operation exceeds the vault's risk limits")]
+    RiskLimitExceeded,
+    #[msg("Invalid oracle price account")]
+    InvalidOracleAccount,
+    #[msg("Oracle price is stale")]
+    OraclePriceStale,
+    #[msg("Oracle confidence interval too wide")]
+    OracleConfidenceTooWide,
+    #[msg("Yield adapter token movement does not match the request")]
+    YieldAdapterMismatch,
+    #[msg("Yield target weights exceed 100%")]
+    InvalidYieldWeights,
+    #[msg("Rebalance does not move toward target weights")]
+    RebalanceNotTowardTarget,
+    #[msg("Yield strategy is impaired")]
+    YieldStrategyImpaired,
+    #[msg("A redemption ticket is already open for this strategy")]
+    RedemptionTicketOpen,
+    #[msg("Yield strategy allocation cap exceeded")]
+    YieldStrategyCapExceeded,
+    #[msg("Deposit would breach the vault's liquid reserve")]
+    LiquidReserveBreached,
+    #[msg("Auto-compound is disabled or not yet due for this vault")]
+    CompoundNotDue,
+    #[msg("Fee schedule exceeds the protocol maximum")]
+    InvalidFeeSchedule,
+    #[msg("Integrator program is not approved for this vault")]
+    IntegratorNotApproved,
+    #[msg("Lock exceeds the integrator's cap for this vault")]
+    IntegratorCapExceeded,
+    #[msg("Integrator still holds locked collateral in this vault")]
+    IntegratorHasLocks,
+    #[msg("Integrator is disabled")]
+    IntegratorDisabled,
+    #[msg("Integrator lacks the capability for this operation")]
+    MissingCapability,
+    #[msg("Lock exceeds the integrator's protocol-wide exposure cap")]
+    IntegratorExposureExceeded,
+    #[msg("Integrator name too long")]
+    NameTooLong,
+    #[msg("Unknown capability flags")]
+    InvalidCapabilities,
+    #[msg("Integrator is deprecated")]
+    IntegratorDeprecated,
+    #[msg("Integrator is not deprecated or its grace period has not ended")]
+    IntegratorGracePeriodActive,
+    #[msg("Invalid lock lease duration")]
+    InvalidLeaseDuration,
+    #[msg("Lock lease has not lapsed past its grace period")]
+    LockLeaseActive,
+    #[msg("Lock lease is not close to expiry")]
+    LockLeaseNotExpiring,
+    #[msg("Withdrawal would leave less collateral than the required margin")]
+    InsufficientFreeMargin,
+    #[msg("Position summary is too old")]
+    PositionSummaryStale,
+    #[msg("No risk attestation from the integrator's signer in this transaction")]
+    AttestationMissing,
+    #[msg("Malformed risk attestation")]
+    AttestationInvalid,
+    #[msg("Risk attestation expired or valid for too long")]
+    AttestationExpired,
+    #[msg("Risk attestation nonce already used")]
+    AttestationReplayed,
+    #[msg("Amount above the co-signer threshold needs the co-signer's signature or approval")]
+    CoSignerRequired,
+    #[msg("Co-signer approval does not match this amount and recipient")]
+    CoSignerApprovalInvalid,
+    #[msg("Co-signer approval expired or valid for too long")]
+    CoSignerApprovalExpired,
+    #[msg("Co-signer approval nonce already used")]
+    CoSignerApprovalReplayed,
+    #[msg("Co-signer policy change is still in its delay period")]
+    PolicyChangeNotReady,
+    #[msg("Co-signer must be a key other than the vault owner")]
+    InvalidCoSignerPolicy,
+    #[msg("Vault is panic-locked")]
+    VaultPanicLocked,
+    #[msg("Vault is not panic-locked or no unlock was requested")]
+    VaultNotPanicLocked,
+    #[msg("Panic unlock cooldown has not passed")]
+    PanicUnlockCooldownActive,
+    #[msg("Guardians must be unique, exclude the owner and meet a threshold between 1 and their count")]
+    InvalidGuardianSet,
+    #[msg("Vault has no recovery guardians")]
+    RecoveryNotConfigured,
+    #[msg("An owner recovery is already pending")]
+    RecoveryPending,
+    #[msg("Owner recovery delay has not passed")]
+    RecoveryDelayActive,
+    #[msg("Recovered owner must be a new, non-default key")]
+    InvalidRecoveryOwner,
+    #[msg(
+        "Beneficiary must differ from the owner and wait at least the minimum inactivity period"
+    )]
+    InvalidBeneficiary,
+    #[msg("The owner has not been inactive for the full inactivity period")]
+    InheritanceNotClaimable,
+    #[msg("Account is not in a layout this instruction can migrate")]
+    AccountAlreadyMigrated,
+    #[msg("Oracle publish time is ahead of the cluster clock")]
+    OraclePublishTimeInFuture,
 }
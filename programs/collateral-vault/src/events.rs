@@ -1,4 +1,4 @@
-use crate::types::TransactionType;
+use crate::types::{FeeOperation, PolicyChange, TransactionType};
 use anchor_lang::prelude::*;
 
 #[event]
@@ -15,6 +15,7 @@ pub struct WithdrawEvent {
     pub vault: Pubkey,
     pub owner: Pubkey,
     pub amount: u64,
+    pub fee: u64,
     pub new_total_balance: u64,
     pub new_available_balance: u64,
 }
@@ -42,10 +43,21 @@ pub struct TransferEvent {
     pub from_vault: Pubkey,
     pub to_vault: Pubkey,
     pub amount: u64,
+    pub fee: u64,
     pub from_new_total_balance: u64,
     pub to_new_total_balance: u64,
 }
 
+#[event]
+pub struct BatchSettleEvent {
+    pub caller_program: Pubkey,
+    pub entries: u32,
+    pub transfers: u32,
+    pub gross_amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TransactionEvent {
     pub vault: Pubkey,
@@ -61,6 +73,7 @@ pub struct EmergencyWithdrawEvent {
     pub owner: Pubkey,
     pub authority: Pubkey,
     pub amount: u64,
+    pub fee: u64,
     pub new_total_balance: u64,
     pub new_available_balance: u64,
     pub new_locked_balance: u64,
@@ -86,18 +99,25 @@ pub struct TimelocksReleasedEvent {
 #[event]
 pub struct VaultAuthorityInitializedEvent {
     pub governance: Pubkey,
-    pub authorized_programs_len: u32,
     pub freeze: bool,
 }
 
 #[event]
-pub struct AuthorizedProgramAddedEvent {
+pub struct IntegratorRegisteredEvent {
     pub program: Pubkey,
+    pub capabilities: u8,
+    pub max_total_locked: Option<u64>,
+    pub name: String,
+    pub version: u32,
 }
 
 #[event]
-pub struct AuthorizedProgramRemovedEvent {
+pub struct IntegratorUpdatedEvent {
     pub program: Pubkey,
+    pub capabilities: u8,
+    pub enabled: bool,
+    pub max_total_locked: Option<u64>,
+    pub version: u32,
 }
 
 #[event]
@@ -110,6 +130,34 @@ pub struct CpiEnforcedSetEvent {
     pub cpi_enforced: bool,
 }
 
+#[event]
+pub struct RiskParamsUpdated {
+    pub level: u8,
+    pub max_locked_bps: u16,
+    pub max_yield_bps: u16,
+    pub min_free_buffer_bps: u16,
+}
+
+#[event]
+pub struct RiskLevelSetEvent {
+    pub risk_level: u8,
+}
+
+#[event]
+pub struct VaultRiskTierSetEvent {
+    pub vault: Pubkey,
+    pub tier_override: Option<u8>,
+}
+
+#[event]
+pub struct CollateralConfigSetEvent {
+    pub mint: Pubkey,
+    pub price_feed: Pubkey,
+    pub haircut_bps: u16,
+    pub max_staleness_seconds: i64,
+    pub max_confidence_bps: u16,
+}
+
 #[event]
 pub struct UpdateUsdtMintEvent {
     pub vault: Pubkey,
@@ -129,6 +177,7 @@ pub struct YieldDepositEvent {
     pub owner: Pubkey,
     pub program: Pubkey,
     pub amount: u64,
+    pub receipt_amount: u64,
     pub new_yield_balance: u64,
     pub new_available_balance: u64,
 }
@@ -139,6 +188,7 @@ pub struct YieldWithdrawEvent {
     pub owner: Pubkey,
     pub program: Pubkey,
     pub amount: u64,
+    pub receipt_amount: u64,
     pub new_yield_balance: u64,
     pub new_available_balance: u64,
 }
@@ -149,10 +199,183 @@ pub struct YieldCompoundEvent {
     pub owner: Pubkey,
     pub program: Pubkey,
     pub compounded_amount: u64,
+    pub claimed_amount: u64,
     pub new_yield_balance: u64,
     pub last_compounded_at: i64,
 }
 
+#[event]
+pub struct YieldTargetWeightSetEvent {
+    pub vault: Pubkey,
+    pub program: Pubkey,
+    pub target_weight_bps: u16,
+    pub total_target_weight_bps: u16,
+}
+
+#[event]
+pub struct YieldRebalanceEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub from_program: Pubkey,
+    pub to_program: Pubkey,
+    pub withdrawn_amount: u64,
+    pub deposited_amount: u64,
+    pub from_deposited: u64,
+    pub to_deposited: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct YieldLossEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub principal_loss: u64,
+    pub accrued_written_off: u64,
+    pub new_yield_balance: u64,
+    pub new_total_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct YieldStrategyImpairedSetEvent {
+    pub program: Pubkey,
+    pub impaired: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct YieldRedemptionRequestedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub requested_amount: u64,
+    pub immediate_amount: u64,
+    pub pending_amount: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct YieldRedemptionClaimedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub claimed_amount: u64,
+    pub filled_amount: u64,
+    pub remaining_amount: u64,
+    pub timed_out: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct YieldStrategyCapsSetEvent {
+    pub program: Pubkey,
+    pub max_deposit: u64,
+    pub max_tvl_share_bps: u16,
+    pub total_deposited: u64,
+}
+
+#[event]
+pub struct YieldPullbackRequiredEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub reserve_target: u64,
+    pub available_balance: u64,
+    pub pullback_amount: u64,
+}
+
+#[event]
+pub struct AutoCompoundEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub keeper: Pubkey,
+    pub compounded_amount: u64,
+    pub claimed_amount: u64,
+    pub performance_fee: u64,
+    pub keeper_bounty: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompoundConfigSetEvent {
+    pub compound_interval_seconds: i64,
+    pub performance_fee_bps: u16,
+    pub keeper_bounty_bps: u16,
+}
+
+#[event]
+pub struct AutoCompoundOptOutSetEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub opt_out: bool,
+}
+
+#[event]
+pub struct FeeScheduleSetEvent {
+    pub operation: FeeOperation,
+    pub bps: u16,
+    pub min: u64,
+}
+
+#[event]
+pub struct CustodyFeeSetEvent {
+    pub custody_fee_bps: u16,
+    pub exempt_tiers: u8,
+    pub custody_fee_index: u64,
+}
+
+#[event]
+pub struct CustodyFeeChargedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub accrued: u64,
+    pub collected: u64,
+    pub owed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct IntegratorApprovedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub max_locked: Option<u64>,
+}
+
+#[event]
+pub struct IntegratorRevokedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+}
+
+#[event]
+pub struct TreasurySweptEvent {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryInitializedEvent {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct YieldHarvestedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub position_value: u64,
+    pub gain: u64,
+    pub new_accrued_balance: u64,
+    pub period_seconds: i64,
+    pub realized_apr_bps: u64,
+    pub harvested_at: i64,
+}
+
 // -----------------
 // Security Policy Updates & Requests
 // -----------------
@@ -182,6 +405,13 @@ pub struct WithdrawRateLimitSetEvent {
     pub max_amount: u64,
 }
 
+#[event]
+pub struct LiquidReserveSetEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub min_liquid_reserve_bps: u16,
+}
+
 #[event]
 pub struct WithdrawWhitelistUpdatedEvent {
     pub vault: Pubkey,
@@ -190,3 +420,252 @@ pub struct WithdrawWhitelistUpdatedEvent {
     pub added: bool,
     pub new_len: u32,
 }
+
+#[event]
+pub struct IntegratorDeprecatedEvent {
+    pub program: Pubkey,
+    pub deprecated_at: i64,
+    pub grace_period_ends_at: i64,
+    pub total_locked: u64,
+}
+
+#[event]
+pub struct StrandedLockReleasedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub amount: u64,
+    pub by_governance: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockLeaseRenewedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub lease_expires_at: i64,
+    // Earliest time the owner may release the lock if the lease is not renewed
+    pub releasable_at: i64,
+}
+
+#[event]
+pub struct LockLeaseExpiringEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub locked: u64,
+    pub lease_expires_at: i64,
+    pub releasable_at: i64,
+    pub expired: bool,
+}
+
+#[event]
+pub struct ExpiredLockReleasedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub amount: u64,
+    pub lease_expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockLeaseGraceSetEvent {
+    pub grace_seconds: i64,
+}
+
+#[event]
+pub struct FreeMarginWithdrawalsSetEvent {
+    pub enabled: bool,
+}
+
+#[event]
+pub struct StrictWithdrawalsSetEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub strict: bool,
+}
+
+#[event]
+pub struct RiskSignerSetEvent {
+    pub program: Pubkey,
+    pub risk_signer: Option<Pubkey>,
+}
+
+#[event]
+pub struct RiskAttestationAcceptedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub open_positions: u64,
+    pub required_margin: u64,
+    pub nonce: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct CoSignerPolicyProposedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub signer: Option<Pubkey>,
+    pub threshold: u64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct CoSignerPolicySetEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub signer: Option<Pubkey>,
+    pub threshold: u64,
+}
+
+#[event]
+pub struct CoSignerPolicyCancelledEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct CoSignerApprovedEvent {
+    pub vault: Pubkey,
+    pub co_signer: Pubkey,
+    pub amount: u64,
+    pub recipient: Pubkey,
+    // True when approved by a signed message rather than a transaction signature
+    pub signed_message: bool,
+}
+
+#[event]
+pub struct PolicyChangeQueuedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub change: PolicyChange,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct PolicyChangeCancelledEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub change: PolicyChange,
+}
+
+#[event]
+pub struct PolicyChangeDelayFloorSetEvent {
+    pub seconds: i64,
+}
+
+#[event]
+pub struct VaultPanicLockedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    // Owner, delegate or multisig signer that pulled the lock
+    pub by: Pubkey,
+    pub locked_at: i64,
+}
+
+#[event]
+pub struct PanicUnlockRequestedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub unlocks_at: i64,
+}
+
+#[event]
+pub struct VaultPanicUnlockedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct GuardiansProposedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct GuardiansSetEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct GuardianChangeCancelledEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct RecoveryStartedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct RecoveryVetoedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnerRecoveredEvent {
+    pub vault: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct BeneficiarySetEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub beneficiary: Option<Pubkey>,
+    pub inactivity_period_seconds: i64,
+}
+
+#[event]
+pub struct InheritanceClaimedEvent {
+    pub vault: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct InheritanceWithdrawalEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VaultAuthorityMigratedEvent {
+    pub governance: Pubkey,
+    pub layout_version: u8,
+    // Version-1 CPI allowlist; these programs must be registered as integrators
+    pub dropped_authorized_programs: Vec<Pubkey>,
+}
+
+#[event]
+pub struct VaultMigratedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub layout_version: u8,
+}
+
+#[event]
+pub struct UnattributedLockReleasedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::constants::{TREASURY_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+use crate::events::CustodyFeeChargedEvent;
+use crate::instructions::treasury::collect_fee;
+use crate::state::{CollateralVault, VaultAuthority};
+
+/// Accrue and collect a vault's custody fee. Permissionless: the fee only ever
+/// moves from the vault to the protocol treasury.
+pub fn handler(ctx: Context<AccrueFees>) -> Result<()> {
+    let accounts = ctx.accounts;
+    charge_custody_fee(
+        &mut accounts.vault,
+        &mut accounts.vault_authority,
+        &accounts.vault_token_account,
+        &accounts.treasury_token_account,
+        &accounts.token_program,
+    )
+}
+
+/// Accrue the custody fee into `custody_fee_owed`, then collect whatever the
+/// available balance covers into the treasury.
+pub(crate) fn charge_custody_fee<'info>(
+    vault: &mut Account<'info, CollateralVault>,
+    vault_authority: &mut Account<'info, VaultAuthority>,
+    vault_token_account: &Account<'info, TokenAccount>,
+    treasury_token_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let accrued = vault.accrue_custody_fee(vault_authority, now)?;
+    let collected = vault.take_custody_fee_owed();
+
+    let vault_seed = vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    collect_fee(
+        token_program.to_account_info(),
+        vault_token_account.to_account_info(),
+        treasury_token_account.to_account_info(),
+        vault.to_account_info(),
+        signer,
+        collected,
+    )?;
+
+    // Protocol TVL (net deposits): collected fees leave the vaults
+    vault_authority.total_value_locked =
+        vault_authority.total_value_locked.saturating_sub(collected);
+
+    if accrued > 0 || collected > 0 {
+        emit!(CustodyFeeChargedEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            accrued,
+            collected,
+            owed: vault.custody_fee_owed,
+            timestamp: now,
+        });
+    }
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()
+}
+
+#[derive(Accounts)]
+pub struct AccrueFees<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Protocol treasury for the vault's mint; receives the custody fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, vault.usdt_mint.as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
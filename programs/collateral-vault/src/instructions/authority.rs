@@ -1,69 +1,38 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::{MAX_AUTHORIZED_PROGRAMS, VAULT_AUTHORITY_SEED};
+use crate::constants::{
+    ACCOUNT_LAYOUT_VERSION, BPS_DENOMINATOR, MAX_AUTHORIZED_PROGRAMS, MAX_CUSTODY_FEE_BPS,
+    MAX_KEEPER_BOUNTY_BPS, MAX_PERFORMANCE_FEE_BPS, MAX_RISK_LEVELS, VAULT_AUTHORITY_SEED,
+    YIELD_STRATEGY_SEED,
+};
 use crate::error::ErrorCode;
 use crate::events::{
-    AuthorizedProgramAddedEvent, AuthorizedProgramRemovedEvent, CpiEnforcedSetEvent,
-    FreezeFlagSetEvent, VaultAuthorityInitializedEvent,
+    CompoundConfigSetEvent, CpiEnforcedSetEvent, CustodyFeeSetEvent, FeeScheduleSetEvent,
+    FreeMarginWithdrawalsSetEvent, FreezeFlagSetEvent, LockLeaseGraceSetEvent,
+    PolicyChangeDelayFloorSetEvent, RiskLevelSetEvent, RiskParamsUpdated,
+    VaultAuthorityInitializedEvent, VaultRiskTierSetEvent, YieldStrategyCapsSetEvent,
+    YieldStrategyImpairedSetEvent,
 };
-use crate::state::VaultAuthority;
+use crate::state::{CollateralVault, VaultAuthority, YieldStrategy};
+use crate::types::{FeeOperation, FeeSchedule, RiskParams};
 
 pub fn initialize_vault_authority(
     ctx: Context<InitializeVaultAuthority>,
-    authorized_programs: Vec<Pubkey>,
     freeze: Option<bool>,
 ) -> Result<()> {
-    // Ensure the authorized list does not exceed our conservative cap
-    require!(
-        authorized_programs.len() <= MAX_AUTHORIZED_PROGRAMS,
-        ErrorCode::Overflow
-    );
-
     let va = &mut ctx.accounts.vault_authority;
     va.governance = ctx.accounts.governance.key();
-    va.authorized_programs = authorized_programs;
     va.bump = ctx.bumps.vault_authority;
     va.freeze = freeze.unwrap_or(false);
     va.cpi_enforced = false;
+    va.layout_version = ACCOUNT_LAYOUT_VERSION;
     emit!(VaultAuthorityInitializedEvent {
         governance: va.governance,
-        authorized_programs_len: va.authorized_programs.len() as u32,
         freeze: va.freeze,
     });
     Ok(())
 }
 
-pub fn add_authorized_program(ctx: Context<UpdateVaultAuthority>, program: Pubkey) -> Result<()> {
-    let va = &mut ctx.accounts.vault_authority;
-    // Prevent duplicates
-    require!(
-        !va.authorized_programs.iter().any(|p| *p == program),
-        ErrorCode::AlreadyExists
-    );
-    // Enforce capacity bound
-    require!(
-        va.authorized_programs.len() < MAX_AUTHORIZED_PROGRAMS,
-        ErrorCode::Overflow
-    );
-    va.authorized_programs.push(program);
-    emit!(AuthorizedProgramAddedEvent { program });
-    Ok(())
-}
-
-pub fn remove_authorized_program(
-    ctx: Context<UpdateVaultAuthority>,
-    program: Pubkey,
-) -> Result<()> {
-    let va = &mut ctx.accounts.vault_authority;
-    if let Some(index) = va.authorized_programs.iter().position(|p| *p == program) {
-        va.authorized_programs.swap_remove(index);
-        emit!(AuthorizedProgramRemovedEvent { program });
-        Ok(())
-    } else {
-        err!(ErrorCode::NotFound)
-    }
-}
-
 pub fn set_freeze_flag(ctx: Context<UpdateVaultAuthority>, freeze: bool) -> Result<()> {
     let va = &mut ctx.accounts.vault_authority;
     va.freeze = freeze;
@@ -78,6 +47,18 @@ pub fn set_cpi_enforced(ctx: Context<UpdateVaultAuthority>, cpi_enforced: bool)
     Ok(())
 }
 
+/// Allow withdrawals while positions are open, bounded by the required margin
+/// integrators report in extended position summaries. Off keeps the strict rule.
+pub fn set_free_margin_withdrawals(
+    ctx: Context<UpdateVaultAuthority>,
+    enabled: bool,
+) -> Result<()> {
+    ctx.accounts.vault_authority.free_margin_withdrawals = enabled;
+    emit!(FreeMarginWithdrawalsSetEvent { enabled });
+    Ok(())
+}
+
+#[allow(clippy::manual_contains)]
 pub fn add_yield_program(ctx: Context<UpdateVaultAuthority>, program: Pubkey) -> Result<()> {
     let va = &mut ctx.accounts.vault_authority;
     require!(
@@ -103,8 +84,199 @@ pub fn remove_yield_program(ctx: Context<UpdateVaultAuthority>, program: Pubkey)
 }
 
 pub fn set_risk_level(ctx: Context<UpdateVaultAuthority>, risk_level: u8) -> Result<()> {
+    require!(
+        (risk_level as usize) < MAX_RISK_LEVELS,
+        ErrorCode::InvalidRiskParams
+    );
     let va = &mut ctx.accounts.vault_authority;
     va.risk_level = risk_level;
+    emit!(RiskLevelSetEvent { risk_level });
+    Ok(())
+}
+
+pub fn set_risk_params(
+    ctx: Context<UpdateVaultAuthority>,
+    level: u8,
+    params: RiskParams,
+) -> Result<()> {
+    require!(
+        (level as usize) < MAX_RISK_LEVELS,
+        ErrorCode::InvalidRiskParams
+    );
+    params.validate()?;
+
+    let va = &mut ctx.accounts.vault_authority;
+    let index = level as usize;
+    // Levels are stored densely; unset levels in between stay unconstrained
+    while va.risk_params.len() <= index {
+        va.risk_params.push(RiskParams {
+            max_locked_bps: crate::constants::BPS_DENOMINATOR as u16,
+            max_yield_bps: crate::constants::BPS_DENOMINATOR as u16,
+            min_free_buffer_bps: 0,
+        });
+    }
+    va.risk_params[index] = params;
+
+    emit!(RiskParamsUpdated {
+        level,
+        max_locked_bps: params.max_locked_bps,
+        max_yield_bps: params.max_yield_bps,
+        min_free_buffer_bps: params.min_free_buffer_bps,
+    });
+    Ok(())
+}
+
+pub fn set_vault_risk_tier(ctx: Context<SetVaultRiskTier>, tier: Option<u8>) -> Result<()> {
+    if let Some(t) = tier {
+        require!((t as usize) < MAX_RISK_LEVELS, ErrorCode::InvalidRiskParams);
+    }
+    let vault = &mut ctx.accounts.vault;
+    vault.risk_tier_override = tier;
+    emit!(VaultRiskTierSetEvent {
+        vault: vault.key(),
+        tier_override: tier,
+    });
+    Ok(())
+}
+
+/// Mark a yield program as impaired (or clear the flag). Impaired strategies
+/// reject new deposits; existing positions can still be withdrawn.
+pub fn set_yield_strategy_impaired(
+    ctx: Context<UpdateYieldStrategy>,
+    impaired: bool,
+) -> Result<()> {
+    let strategy = &mut ctx.accounts.yield_strategy;
+    strategy.yield_program = ctx.accounts.yield_program.key();
+    strategy.bump = ctx.bumps.yield_strategy;
+    strategy.impaired = impaired;
+    strategy.impaired_updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(YieldStrategyImpairedSetEvent {
+        program: strategy.yield_program,
+        impaired,
+        timestamp: strategy.impaired_updated_at,
+    });
+    Ok(())
+}
+
+/// Configure the permissionless compound crank. An interval of 0 disables it.
+pub fn set_compound_config(
+    ctx: Context<UpdateVaultAuthority>,
+    compound_interval_seconds: i64,
+    performance_fee_bps: u16,
+    keeper_bounty_bps: u16,
+) -> Result<()> {
+    require!(compound_interval_seconds >= 0, ErrorCode::InvalidAmount);
+    require!(
+        performance_fee_bps <= MAX_PERFORMANCE_FEE_BPS
+            && keeper_bounty_bps <= MAX_KEEPER_BOUNTY_BPS,
+        ErrorCode::InvalidRiskParams
+    );
+    let va = &mut ctx.accounts.vault_authority;
+    va.compound_interval_seconds = compound_interval_seconds;
+    va.performance_fee_bps = performance_fee_bps;
+    va.keeper_bounty_bps = keeper_bounty_bps;
+    emit!(CompoundConfigSetEvent {
+        compound_interval_seconds,
+        performance_fee_bps,
+        keeper_bounty_bps,
+    });
+    Ok(())
+}
+
+/// Set the fee charged on one operation. Fees are taken out of the amount moved
+/// and paid to the treasury for the vault's mint.
+pub fn set_fee_schedule(
+    ctx: Context<UpdateVaultAuthority>,
+    operation: FeeOperation,
+    schedule: FeeSchedule,
+) -> Result<()> {
+    schedule.validate()?;
+    let va = &mut ctx.accounts.vault_authority;
+    match operation {
+        FeeOperation::Withdraw => va.withdraw_fee = schedule,
+        FeeOperation::Transfer => va.transfer_fee = schedule,
+        FeeOperation::EmergencyWithdraw => va.emergency_withdraw_fee = schedule,
+    }
+    emit!(FeeScheduleSetEvent {
+        operation,
+        bps: schedule.bps,
+        min: schedule.min,
+    });
+    Ok(())
+}
+
+/// Set the annual custody fee and the risk tiers exempt from it (bit i = tier i).
+/// The index is checkpointed first so past time accrues at the old rate.
+pub fn set_custody_fee(
+    ctx: Context<UpdateVaultAuthority>,
+    custody_fee_bps: u16,
+    exempt_tiers: u8,
+) -> Result<()> {
+    require!(
+        custody_fee_bps <= MAX_CUSTODY_FEE_BPS,
+        ErrorCode::InvalidFeeSchedule
+    );
+    let now = Clock::get()?.unix_timestamp;
+    let va = &mut ctx.accounts.vault_authority;
+    va.custody_fee_index = va.custody_fee_index_at(now);
+    va.custody_fee_index_updated_at = now;
+    va.custody_fee_bps = custody_fee_bps;
+    va.custody_fee_exempt_tiers = exempt_tiers;
+    emit!(CustodyFeeSetEvent {
+        custody_fee_bps,
+        exempt_tiers,
+        custody_fee_index: va.custody_fee_index,
+    });
+    Ok(())
+}
+
+/// Set how long after a lock lease lapses the vault owner must wait before
+/// releasing the lock themselves.
+pub fn set_lock_lease_grace(ctx: Context<UpdateVaultAuthority>, grace_seconds: i64) -> Result<()> {
+    require!(grace_seconds >= 0, ErrorCode::InvalidLeaseDuration);
+    ctx.accounts.vault_authority.lock_lease_grace_seconds = grace_seconds;
+    emit!(LockLeaseGraceSetEvent { grace_seconds });
+    Ok(())
+}
+
+/// Minimum wait before any vault's security relaxations (shorter withdraw delay,
+/// higher rate limit, new whitelist address or delegate, weaker multisig) apply.
+pub fn set_policy_change_delay_floor(
+    ctx: Context<UpdateVaultAuthority>,
+    seconds: i64,
+) -> Result<()> {
+    require!(seconds >= 0, ErrorCode::InvalidAmount);
+    ctx.accounts
+        .vault_authority
+        .policy_change_delay_floor_seconds = seconds;
+    emit!(PolicyChangeDelayFloorSetEvent { seconds });
+    Ok(())
+}
+
+/// Cap the principal all vaults together may deploy into a strategy, as an
+/// absolute amount and as a share of protocol TVL (0 disables either cap).
+pub fn set_yield_strategy_caps(
+    ctx: Context<UpdateYieldStrategy>,
+    max_deposit: u64,
+    max_tvl_share_bps: u16,
+) -> Result<()> {
+    require!(
+        (max_tvl_share_bps as u64) <= BPS_DENOMINATOR,
+        ErrorCode::InvalidRiskParams
+    );
+    let strategy = &mut ctx.accounts.yield_strategy;
+    strategy.yield_program = ctx.accounts.yield_program.key();
+    strategy.bump = ctx.bumps.yield_strategy;
+    strategy.max_deposit = max_deposit;
+    strategy.max_tvl_share_bps = max_tvl_share_bps;
+
+    emit!(YieldStrategyCapsSetEvent {
+        program: strategy.yield_program,
+        max_deposit,
+        max_tvl_share_bps,
+        total_deposited: strategy.total_deposited,
+    });
     Ok(())
 }
 
@@ -139,3 +311,45 @@ pub struct UpdateVaultAuthority<'info> {
     )]
     pub vault_authority: Account<'info, VaultAuthority>,
 }
+
+#[derive(Accounts)]
+pub struct SetVaultRiskTier<'info> {
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    #[account(mut)]
+    pub vault: Account<'info, CollateralVault>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateYieldStrategy<'info> {
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// CHECK: program id only; used as PDA seed
+    pub yield_program: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = governance,
+        space = YieldStrategy::LEN,
+        seeds = [YIELD_STRATEGY_SEED, yield_program.key().as_ref()],
+        bump
+    )]
+    pub yield_strategy: Account<'info, YieldStrategy>,
+
+    pub system_program: Program<'info, System>,
+}
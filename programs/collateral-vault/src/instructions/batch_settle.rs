@@ -0,0 +1,277 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::constants::{
+    INTEGRATOR_SEED, MAX_SETTLEMENT_ENTRIES, TREASURY_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED,
+};
+use crate::error::ErrorCode;
+use crate::events::{BatchSettleEvent, TransactionEvent};
+use crate::instructions::lock_collateral::resolve_caller_program;
+use crate::instructions::treasury::collect_fee;
+use crate::state::integrator_registry::CAP_SETTLE;
+use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
+use crate::types::{SettlementEntry, TransactionType};
+
+/// Settle many vaults at once. Each entry applies a signed delta to a vault's
+/// available balance; deltas must sum to zero. Token movements are netted so
+/// that at most `entries - 1` SPL transfers are issued. Every debited vault also
+/// pays the transfer fee on its leg to the treasury, on top of its delta, so
+/// receivers are credited in full.
+///
+/// Remaining accounts: `[vault, vault_token_account]` for every entry, in order.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchSettle<'info>>,
+    entries: Vec<SettlementEntry>,
+) -> Result<()> {
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_SETTLEMENT_ENTRIES,
+        ErrorCode::InvalidSettlement
+    );
+    require!(
+        ctx.remaining_accounts.len() == entries.len() * 2,
+        ErrorCode::InvalidSettlement
+    );
+
+    let va = &ctx.accounts.vault_authority;
+    // Optional global freeze
+    require!(!va.freeze, ErrorCode::Frozen);
+
+    let actual_caller = resolve_caller_program(&ctx.accounts.instructions)?;
+    ctx.accounts
+        .integrator
+        .require_capability(actual_caller, CAP_SETTLE)?;
+    if va.cpi_enforced {
+        require_keys_eq!(
+            ctx.accounts.caller_program.key(),
+            actual_caller,
+            ErrorCode::UnauthorizedProgram
+        );
+    }
+
+    // Deltas must net to zero: settlement only moves collateral between vaults
+    let net: i128 = entries.iter().map(|e| e.delta as i128).sum();
+    require!(net == 0, ErrorCode::UnbalancedSettlement);
+
+    let token_program_key = ctx.accounts.token_program.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut vaults: Vec<Account<'info, CollateralVault>> = Vec::with_capacity(entries.len());
+    let mut token_accounts: Vec<Account<'info, TokenAccount>> = Vec::with_capacity(entries.len());
+    let mut seen: std::collections::BTreeSet<Pubkey> = std::collections::BTreeSet::new();
+    let mint = ctx.accounts.treasury_token_account.mint;
+
+    for (i, entry) in entries.iter().enumerate() {
+        let vault_ai = &ctx.remaining_accounts[i * 2];
+        let token_ai = &ctx.remaining_accounts[i * 2 + 1];
+
+        require_keys_eq!(vault_ai.key(), entry.vault, ErrorCode::InvalidSettlement);
+        require!(
+            vault_ai.is_writable && token_ai.is_writable,
+            ErrorCode::InvalidSettlement
+        );
+        // A vault may appear only once per batch
+        require!(seen.insert(entry.vault), ErrorCode::AlreadyExists);
+
+        let vault: Account<'info, CollateralVault> = Account::try_from(vault_ai)?;
+        let token_account: Account<'info, TokenAccount> = Account::try_from(token_ai)?;
+
+        // All legs must settle in the treasury's collateral mint
+        require_keys_eq!(vault.usdt_mint, mint, ErrorCode::Unauthorized);
+        require_keys_eq!(token_account.mint, mint, ErrorCode::Unauthorized);
+        require_keys_eq!(token_account.owner, vault.key(), ErrorCode::Unauthorized);
+        require_keys_eq!(
+            *token_ai.owner,
+            token_program_key,
+            ErrorCode::InvalidTokenProgramOwner
+        );
+
+        vaults.push(vault);
+        token_accounts.push(token_account);
+    }
+
+    // Apply every delta to the books first; any vault going negative aborts the batch
+    let mut gross_amount: u64 = 0;
+    let mut fees: Vec<u64> = vec![0; entries.len()];
+    for ((vault, entry), fee) in vaults.iter_mut().zip(entries.iter()).zip(fees.iter_mut()) {
+        let amount = entry.delta.unsigned_abs();
+        if entry.delta < 0 {
+            // Only vaults that approved the calling integrator can be debited
+            vault.require_integrator(actual_caller)?;
+            require!(!vault.is_panic_locked(), ErrorCode::VaultPanicLocked);
+            *fee = ctx.accounts.vault_authority.transfer_fee.fee_for(amount);
+            let debit = amount.checked_add(*fee).ok_or(ErrorCode::Overflow)?;
+            require!(
+                vault.available_balance >= debit,
+                ErrorCode::InsufficientFunds
+            );
+            vault.total_balance = vault
+                .total_balance
+                .checked_sub(debit)
+                .ok_or(ErrorCode::Overflow)?;
+            vault.available_balance = vault
+                .available_balance
+                .checked_sub(debit)
+                .ok_or(ErrorCode::Overflow)?;
+            gross_amount = gross_amount
+                .checked_add(amount)
+                .ok_or(ErrorCode::Overflow)?;
+        } else {
+            vault.total_balance = vault
+                .total_balance
+                .checked_add(amount)
+                .ok_or(ErrorCode::Overflow)?;
+            vault.available_balance = vault
+                .available_balance
+                .checked_add(amount)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+        vault.check_balance_invariant()?;
+    }
+
+    // Net the token movements: each transfer is signed by the paying vault PDA
+    let deltas: Vec<i64> = entries.iter().map(|e| e.delta).collect();
+    let transfers = net_settlement(&deltas)?;
+    for (from, to, amount) in transfers.iter() {
+        let from_vault = &vaults[*from];
+        let vault_seed = from_vault.original_owner;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[from_vault.bump]];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        let cpi_accounts = anchor_spl::token::Transfer {
+            from: token_accounts[*from].to_account_info(),
+            to: token_accounts[*to].to_account_info(),
+            authority: from_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        anchor_spl::token::transfer(cpi_ctx, *amount)?;
+    }
+
+    // Each debited vault pays its own fee into the treasury
+    let mut total_fee: u64 = 0;
+    for ((vault, token_account), fee) in vaults.iter().zip(token_accounts.iter()).zip(fees) {
+        if fee == 0 {
+            continue;
+        }
+        let vault_seed = vault.original_owner;
+        let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[vault.bump]];
+        collect_fee(
+            ctx.accounts.token_program.to_account_info(),
+            token_account.to_account_info(),
+            ctx.accounts.treasury_token_account.to_account_info(),
+            vault.to_account_info(),
+            &[signer_seeds],
+            fee,
+        )?;
+        total_fee = total_fee.checked_add(fee).ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Protocol TVL (net deposits): the fees leave the vaults
+    let va = &mut ctx.accounts.vault_authority;
+    va.total_value_locked = va.total_value_locked.saturating_sub(total_fee);
+
+    // Persist updated books for every vault in the batch
+    for (vault, entry) in vaults.iter().zip(entries.iter()) {
+        vault.exit(&crate::ID)?;
+        if entry.delta != 0 {
+            emit!(TransactionEvent {
+                vault: vault.key(),
+                owner: vault.owner,
+                transaction_type: TransactionType::Settlement,
+                amount: entry.delta.unsigned_abs(),
+                timestamp: now,
+            });
+        }
+    }
+
+    emit!(BatchSettleEvent {
+        caller_program: actual_caller,
+        entries: entries.len() as u32,
+        transfers: transfers.len() as u32,
+        gross_amount,
+        fee: total_fee,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Reduce a zero-sum list of deltas to `(from_index, to_index, amount)` transfers.
+/// Payers and receivers are matched greedily, so the number of transfers is at
+/// most `payers + receivers - 1`.
+pub fn net_settlement(deltas: &[i64]) -> Result<Vec<(usize, usize, u64)>> {
+    let mut payers: Vec<(usize, u64)> = Vec::new();
+    let mut receivers: Vec<(usize, u64)> = Vec::new();
+    for (i, d) in deltas.iter().enumerate() {
+        if *d < 0 {
+            payers.push((i, d.unsigned_abs()));
+        } else if *d > 0 {
+            receivers.push((i, d.unsigned_abs()));
+        }
+    }
+
+    let mut transfers: Vec<(usize, usize, u64)> = Vec::new();
+    let (mut p, mut r) = (0usize, 0usize);
+    while p < payers.len() && r < receivers.len() {
+        let amount = core::cmp::min(payers[p].1, receivers[r].1);
+        transfers.push((payers[p].0, receivers[r].0, amount));
+        payers[p].1 = payers[p].1.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
+        receivers[r].1 = receivers[r]
+            .1
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        if payers[p].1 == 0 {
+            p += 1;
+        }
+        if receivers[r].1 == 0 {
+            r += 1;
+        }
+    }
+
+    // Anything left over means the deltas did not net to zero
+    require!(
+        payers[p..].iter().all(|(_, a)| *a == 0) && receivers[r..].iter().all(|(_, a)| *a == 0),
+        ErrorCode::UnbalancedSettlement
+    );
+
+    Ok(transfers)
+}
+
+#[derive(Accounts)]
+pub struct BatchSettle<'info> {
+    /// CHECK: program id of the calling program, used for registry lookup
+    pub caller_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// Registry record of the calling program; checked against the actual caller
+    #[account(
+        seeds = [INTEGRATOR_SEED, caller_program.key().as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
+    /// CHECK: Instructions sysvar account for CPI-origin verification when enforced
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Protocol treasury for the batch's collateral mint; receives the transfer fees
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, treasury_token_account.mint.as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
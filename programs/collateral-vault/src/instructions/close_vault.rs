@@ -18,8 +18,8 @@ pub fn handler(ctx: Context<CloseVault>) -> Result<()> {
     );
 
     // Close the vault's ATA (authority is vault PDA)
-    let user_key = ctx.accounts.user.key();
-    let signer_seeds: &[&[u8]] = &[VAULT_SEED, user_key.as_ref(), &[vault.bump]];
+    let vault_seed = vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[vault.bump]];
     let signer: &[&[&[u8]]] = &[signer_seeds];
 
     let cpi_accounts = CloseAccount {
@@ -50,10 +50,11 @@ pub struct CloseVault<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, user.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         close = user,
         constraint = vault.owner == user.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
 
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+use crate::attestation::find_co_signer_approval;
+use crate::error::ErrorCode;
+use crate::events::{
+    CoSignerApprovedEvent, CoSignerPolicyCancelledEvent, CoSignerPolicyProposedEvent,
+    CoSignerPolicySetEvent,
+};
+use crate::instructions::withdraw_policy::{RestrictPolicy, UpdatePolicy};
+use crate::state::CollateralVault;
+use crate::types::CoSignerPolicy;
+
+/// Set, change or (with None) remove the vault's co-signer policy. Only a first
+/// policy applies at once; changes wait `CO_SIGNER_POLICY_DELAY_SECONDS` and are
+/// then applied with `apply_co_signer_policy`.
+pub fn propose_co_signer_policy(
+    ctx: Context<UpdatePolicy>,
+    policy: Option<CoSignerPolicy>,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    let vault = &mut ctx.accounts.vault;
+    if let Some(p) = policy {
+        require!(
+            p.signer != Pubkey::default() && p.signer != vault.owner,
+            ErrorCode::InvalidCoSignerPolicy
+        );
+    }
+    let now = Clock::get()?.unix_timestamp;
+    let effective_at = vault.propose_co_signer_policy(policy, now)?;
+    if vault.pending_co_signer.is_none() {
+        emit!(CoSignerPolicySetEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            signer: policy.map(|p| p.signer),
+            threshold: policy.map(|p| p.threshold).unwrap_or(0),
+        });
+    } else {
+        emit!(CoSignerPolicyProposedEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            signer: policy.map(|p| p.signer),
+            threshold: policy.map(|p| p.threshold).unwrap_or(0),
+            effective_at,
+        });
+    }
+    Ok(())
+}
+
+/// Apply a proposed co-signer policy change whose delay has passed.
+pub fn apply_co_signer_policy(ctx: Context<UpdatePolicy>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let now = Clock::get()?.unix_timestamp;
+    vault.record_owner_activity(ctx.accounts.owner.key(), &[], now);
+    let policy = vault.apply_pending_co_signer_policy(now)?;
+    emit!(CoSignerPolicySetEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        signer: policy.map(|p| p.signer),
+        threshold: policy.map(|p| p.threshold).unwrap_or(0),
+    });
+    Ok(())
+}
+
+/// Drop a proposed co-signer policy change before it takes effect.
+pub fn cancel_co_signer_policy(ctx: Context<RestrictPolicy>) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    let vault = &mut ctx.accounts.vault;
+    require!(vault.pending_co_signer.is_some(), ErrorCode::NotFound);
+    vault.pending_co_signer = None;
+    emit!(CoSignerPolicyCancelledEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+    });
+    Ok(())
+}
+
+/// Enforce the vault's co-signer policy on an outflow of `amount` to
+/// `recipient`: above the threshold the co-signer must be among `authority` and
+/// the signer `remaining_accounts`, or have signed a matching approval in an
+/// ed25519 instruction of this transaction.
+pub(crate) fn enforce_co_signer(
+    vault: &mut CollateralVault,
+    vault_key: Pubkey,
+    amount: u64,
+    recipient: Pubkey,
+    authority: Pubkey,
+    remaining_accounts: &[AccountInfo],
+    instructions: &AccountInfo,
+) -> Result<()> {
+    let Some(co_signer) = vault.co_signer_for(amount) else {
+        return Ok(());
+    };
+    let signed = authority == co_signer
+        || remaining_accounts
+            .iter()
+            .any(|ai| ai.is_signer && *ai.key == co_signer);
+    if !signed {
+        let approval = find_co_signer_approval(instructions, &co_signer, &vault_key)?;
+        let now = Clock::get()?.unix_timestamp;
+        vault.accept_co_signer_approval(&approval, amount, recipient, now)?;
+    }
+    emit!(CoSignerApprovedEvent {
+        vault: vault_key,
+        co_signer,
+        amount,
+        recipient,
+        signed_message: !signed,
+    });
+    Ok(())
+}
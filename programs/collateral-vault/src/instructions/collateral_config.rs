@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::constants::{BPS_DENOMINATOR, COLLATERAL_CONFIG_SEED, VAULT_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::CollateralConfigSetEvent;
+use crate::oracle::PriceFeed;
+use crate::state::{CollateralConfig, VaultAuthority};
+
+pub fn set_collateral_config(
+    ctx: Context<SetCollateralConfig>,
+    haircut_bps: u16,
+    max_staleness_seconds: i64,
+    max_confidence_bps: u16,
+) -> Result<()> {
+    require!(
+        (haircut_bps as u64) <= BPS_DENOMINATOR,
+        ErrorCode::InvalidRiskParams
+    );
+    require!(
+        (max_confidence_bps as u64) <= BPS_DENOMINATOR,
+        ErrorCode::InvalidRiskParams
+    );
+    require!(max_staleness_seconds > 0, ErrorCode::InvalidAmount);
+
+    // The price account must already hold a feed in the documented layout
+    {
+        let data = ctx.accounts.price_feed.try_borrow_data()?;
+        PriceFeed::unpack(&data)?;
+    }
+
+    let config = &mut ctx.accounts.collateral_config;
+    config.mint = ctx.accounts.mint.key();
+    config.price_feed = ctx.accounts.price_feed.key();
+    config.haircut_bps = haircut_bps;
+    config.max_staleness_seconds = max_staleness_seconds;
+    config.max_confidence_bps = max_confidence_bps;
+    config.bump = ctx.bumps.collateral_config;
+
+    emit!(CollateralConfigSetEvent {
+        mint: config.mint,
+        price_feed: config.price_feed,
+        haircut_bps,
+        max_staleness_seconds,
+        max_confidence_bps,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCollateralConfig<'info> {
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// Oracle price account for this mint
+    /// CHECK: layout is validated via crate::oracle::PriceFeed::unpack
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = governance,
+        space = CollateralConfig::LEN,
+        seeds = [COLLATERAL_CONFIG_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    pub system_program: Program<'info, System>,
+}
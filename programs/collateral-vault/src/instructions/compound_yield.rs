@@ -1,13 +1,22 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
 
-use crate::constants::VAULT_SEED;
+use crate::constants::{VAULT_SEED, YIELD_POSITION_SEED, YIELD_STRATEGY_SEED};
 use crate::error::ErrorCode;
 use crate::events::YieldCompoundEvent;
-use crate::state::{CollateralVault, VaultAuthority};
-use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
-use anchor_lang::solana_program::program::invoke_signed;
+use crate::state::{CollateralVault, VaultAuthority, YieldPosition, YieldStrategy};
+use crate::yield_adapter::{invoke_adapter, AdapterAccounts, AdapterInstruction};
 
-pub fn handler(ctx: Context<CompoundYield>, compounded_amount: u64) -> Result<()> {
+#[allow(clippy::manual_contains)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CompoundYield<'info>>,
+    compounded_amount: u64,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
     // Authorization: single-owner or multisig
     let threshold = ctx.accounts.vault.multisig_threshold;
     if threshold == 0 {
@@ -48,23 +57,99 @@ pub fn handler(ctx: Context<CompoundYield>, compounded_amount: u64) -> Result<()
         require!(approved >= threshold, ErrorCode::Unauthorized);
     }
 
+    compound_position(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        Some(compounded_amount),
+    )?;
+    Ok(())
+}
+
+/// Harvest rewards through the adapter and move `compounded_amount` of the
+/// position's accrued yield into principal (`None` compounds all of it).
+/// Returns `(claimed, compounded)`, where `claimed` is liquid rewards paid to
+/// the vault token account.
+pub(crate) fn compound_position<'info>(
+    accounts: &mut CompoundYield<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    compounded_amount: Option<u64>,
+) -> Result<(u64, u64)> {
     // Whitelist check for yield program
-    let yp = ctx.accounts.yield_program.key();
+    let yp = accounts.yield_program.key();
     require!(
-        ctx.accounts
-            .vault_authority
-            .yield_whitelist
-            .iter()
-            .any(|p| *p == yp),
+        accounts.vault_authority.yield_whitelist.contains(&yp),
         ErrorCode::YieldProgramNotWhitelisted
     );
 
-    let vault = &mut ctx.accounts.vault;
+    // Ask the adapter to reinvest rewards; it must not move collateral out of the vault
+    let vault_before = accounts.vault_token_account.amount;
+    let receipts_before = accounts.receipt_token_account.amount;
+
+    let vault_seed = accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[accounts.vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    invoke_adapter(
+        &accounts.yield_program.to_account_info(),
+        AdapterInstruction::Harvest,
+        AdapterAccounts {
+            vault: accounts.vault.to_account_info(),
+            vault_token_account: accounts.vault_token_account.to_account_info(),
+            receipt_token_account: accounts.receipt_token_account.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        },
+        remaining_accounts,
+        signer,
+    )?;
+
+    accounts.vault_token_account.reload()?;
+    accounts.receipt_token_account.reload()?;
+    // Rewards paid out to the vault token account instead of reinvested
+    let claimed = accounts
+        .vault_token_account
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    require!(
+        accounts.receipt_token_account.amount >= receipts_before,
+        ErrorCode::YieldAdapterMismatch
+    );
+
+    let position = &mut accounts.yield_position;
+    let vault = &mut accounts.vault;
+    if claimed > 0 {
+        // Liquid rewards become available collateral; consume this position's accrued yield first
+        let from_accrued = core::cmp::min(claimed, position.accrued);
+        position.accrued = position
+            .accrued
+            .checked_sub(from_accrued)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.yield_accrued_balance = vault
+            .yield_accrued_balance
+            .checked_sub(from_accrued)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.available_balance = vault
+            .available_balance
+            .checked_add(claimed)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.total_balance = vault
+            .total_balance
+            .checked_add(claimed)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+    let compounded_amount = compounded_amount.unwrap_or(position.accrued);
     if compounded_amount > 0 {
         require!(
-            vault.yield_accrued_balance >= compounded_amount,
+            position.accrued >= compounded_amount,
             ErrorCode::InsufficientYieldBalance
         );
+        position.accrued = position
+            .accrued
+            .checked_sub(compounded_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        position.deposited = position
+            .deposited
+            .checked_add(compounded_amount)
+            .ok_or(ErrorCode::Overflow)?;
         vault.yield_accrued_balance = vault
             .yield_accrued_balance
             .checked_sub(compounded_amount)
@@ -73,48 +158,28 @@ pub fn handler(ctx: Context<CompoundYield>, compounded_amount: u64) -> Result<()
             .yield_deposited_balance
             .checked_add(compounded_amount)
             .ok_or(ErrorCode::Overflow)?;
+        vault.total_balance = vault
+            .total_balance
+            .checked_add(compounded_amount)
+            .ok_or(ErrorCode::Overflow)?;
     }
+    YieldStrategy::record_flows(&accounts.yield_strategy, compounded_amount, 0)?;
     vault.last_compounded_at = Clock::get()?.unix_timestamp;
-    if vault.active_yield_program == Pubkey::default() {
-        vault.active_yield_program = yp;
-    }
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
 
     emit!(YieldCompoundEvent {
         vault: vault.key(),
         owner: vault.owner,
         program: yp,
         compounded_amount,
+        claimed_amount: claimed,
         new_yield_balance: vault.yield_deposited_balance,
         last_compounded_at: vault.last_compounded_at,
     });
 
-    // Optional CPI passthrough to claim/reinvest rewards
-    let signer_seeds: &[&[u8]] = &[
-        crate::constants::VAULT_SEED,
-        ctx.accounts.vault.owner.as_ref(),
-        &[ctx.accounts.vault.bump],
-    ];
-    let signer: &[&[&[u8]]] = &[signer_seeds];
-    let remaining = ctx.remaining_accounts;
-    if !remaining.is_empty() {
-        let metas: Vec<AccountMeta> = remaining
-            .iter()
-            .map(|ai| AccountMeta {
-                pubkey: ai.key(),
-                is_signer: ai.is_signer || ai.key() == ctx.accounts.vault.key(),
-                is_writable: ai.is_writable,
-            })
-            .collect();
-        let program_id = ctx.accounts.yield_program.key();
-        let ix = Instruction {
-            program_id,
-            accounts: metas,
-            data: vec![],
-        };
-        let _ = invoke_signed(&ix, remaining, signer);
-    }
-
-    Ok(())
+    Ok((claimed, compounded_amount))
 }
 
 #[derive(Accounts)]
@@ -128,9 +193,10 @@ pub struct CompoundYield<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
 
@@ -142,6 +208,36 @@ pub struct CompoundYield<'info> {
     pub vault_authority: Account<'info, VaultAuthority>,
 
     /// The yield program to target (whitelisted)
-    /// CHECK: used for key only
+    /// CHECK: key checked against yield_whitelist; must be executable
     pub yield_program: UncheckedAccount<'info>,
+
+    /// Strategy record for cap accounting (may be uninitialized)
+    /// CHECK: address fixed by seeds; updated via YieldStrategy::record_flows
+    #[account(mut, seeds = [YIELD_STRATEGY_SEED, yield_program.key().as_ref()], bump)]
+    pub yield_strategy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Vault-owned token account holding the strategy's receipt tokens
+    #[account(
+        mut,
+        constraint = receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = receipt_token_account.key() != vault_token_account.key() @ ErrorCode::Unauthorized,
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump = yield_position.bump,
+        constraint = yield_position.receipt_token_account == receipt_token_account.key() @ ErrorCode::YieldAdapterMismatch,
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+
+    pub token_program: Program<'info, Token>,
 }
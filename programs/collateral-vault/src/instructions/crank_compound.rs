@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::constants::{BPS_DENOMINATOR, TREASURY_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+use crate::events::AutoCompoundEvent;
+use crate::instructions::compound_yield::*;
+
+/// Compound a vault's yield position on its behalf. Anyone may call this once
+/// `compound_interval_seconds` has passed since the vault last compounded,
+/// unless the owner opted out. All accrued yield of the position is compounded;
+/// a performance fee goes to the treasury and the caller earns a bounty, both
+/// taken as bps of the rewards claimed into the vault. Compounding accrued
+/// yield brings in no tokens, so it is not charged.
+pub fn crank_compound<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankCompound<'info>>,
+) -> Result<()> {
+    let interval = ctx
+        .accounts
+        .compound
+        .vault_authority
+        .compound_interval_seconds;
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &ctx.accounts.compound.vault;
+    require!(
+        interval > 0
+            && !vault.auto_compound_opt_out
+            && now.saturating_sub(vault.last_compounded_at) >= interval,
+        ErrorCode::CompoundNotDue
+    );
+
+    let (claimed, compounded) =
+        compound_position(&mut ctx.accounts.compound, ctx.remaining_accounts, None)?;
+
+    let va = &ctx.accounts.compound.vault_authority;
+    let (keeper_bounty, performance_fee) = compound_fee_split(
+        claimed,
+        va.performance_fee_bps,
+        va.keeper_bounty_bps,
+        ctx.accounts.compound.vault.available_balance,
+    );
+
+    let vault_bump = ctx.accounts.compound.vault.bump;
+    let vault_seed = ctx.accounts.compound.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[vault_bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    for (to, amount) in [
+        (
+            ctx.accounts.keeper_token_account.to_account_info(),
+            keeper_bounty,
+        ),
+        (
+            ctx.accounts.treasury_token_account.to_account_info(),
+            performance_fee,
+        ),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let cpi_accounts = anchor_spl::token::Transfer {
+            from: ctx.accounts.compound.vault_token_account.to_account_info(),
+            to,
+            authority: ctx.accounts.compound.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.compound.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        anchor_spl::token::transfer(cpi_ctx, amount)?;
+    }
+
+    let paid = keeper_bounty
+        .checked_add(performance_fee)
+        .ok_or(ErrorCode::Overflow)?;
+    let vault = &mut ctx.accounts.compound.vault;
+    vault.available_balance = vault
+        .available_balance
+        .checked_sub(paid)
+        .ok_or(ErrorCode::InsufficientFunds)?;
+    vault.total_balance = vault
+        .total_balance
+        .checked_sub(paid)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    emit!(AutoCompoundEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program: ctx.accounts.compound.yield_program.key(),
+        keeper: ctx.accounts.compound.authority.key(),
+        compounded_amount: compounded,
+        claimed_amount: claimed,
+        performance_fee,
+        keeper_bounty,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Split the `claimed` rewards of a crank into `(keeper_bounty, performance_fee)`.
+/// Both are bps of `claimed` and together never exceed `claimed` or `available`,
+/// so they are never paid out of principal; the keeper is paid first so the
+/// crank stays worth calling.
+pub fn compound_fee_split(
+    claimed: u64,
+    performance_fee_bps: u16,
+    keeper_bounty_bps: u16,
+    available: u64,
+) -> (u64, u64) {
+    let bps_of = |bps: u16| ((claimed as u128) * (bps as u128) / (BPS_DENOMINATOR as u128)) as u64;
+    let budget = claimed.min(available);
+    let keeper_bounty = bps_of(keeper_bounty_bps).min(budget);
+    let performance_fee = bps_of(performance_fee_bps).min(budget - keeper_bounty);
+    (keeper_bounty, performance_fee)
+}
+
+#[derive(Accounts)]
+pub struct CrankCompound<'info> {
+    /// Compound accounts; `authority` is the keeper and needs no vault role
+    pub compound: CompoundYield<'info>,
+
+    /// Protocol treasury for the vault's collateral mint
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, compound.vault.usdt_mint.as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    /// Keeper's collateral token account receiving the bounty
+    #[account(
+        mut,
+        constraint = keeper_token_account.mint == compound.vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub keeper_token_account: Account<'info, TokenAccount>,
+}
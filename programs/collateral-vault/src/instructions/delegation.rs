@@ -1,11 +1,20 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::{MAX_DELEGATES, VAULT_SEED};
+use crate::constants::{MAX_DELEGATES, VAULT_AUTHORITY_SEED, VAULT_SEED};
 use crate::error::ErrorCode;
-use crate::state::CollateralVault;
+use crate::instructions::policy_changes::submit_policy_change;
+use crate::state::{CollateralVault, VaultAuthority};
+use crate::types::PolicyChange;
 
+#[allow(clippy::manual_contains)]
 pub fn add_delegate(ctx: Context<UpdateDelegates>, delegate: Pubkey) -> Result<()> {
-    let vault = &mut ctx.accounts.vault;
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    let vault = &ctx.accounts.vault;
+    require!(!vault.is_panic_locked(), ErrorCode::VaultPanicLocked);
     // prevent owner-self as a stored delegate (not harmful, but redundant)
     require!(delegate != vault.owner, ErrorCode::AlreadyExists);
     // prevent duplicates
@@ -15,15 +24,33 @@ pub fn add_delegate(ctx: Context<UpdateDelegates>, delegate: Pubkey) -> Result<(
     );
     // enforce capacity bound (Anchor will allocate space based on LEN)
     require!(vault.delegates.len() < MAX_DELEGATES, ErrorCode::Overflow);
-    vault.delegates.push(delegate);
-    Ok(())
+    // a new delegate can withdraw, so it waits out the policy change delay
+    submit_policy_change(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_authority,
+        PolicyChange::AddDelegate { delegate },
+    )
 }
 
+/// Removal narrows who can withdraw and applies at once, dropping a queued
+/// addition of the same key.
 pub fn remove_delegate(ctx: Context<UpdateDelegates>, delegate: Pubkey) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
     let vault = &mut ctx.accounts.vault;
+    let queued = vault.pending_policy_changes.len();
+    vault
+        .pending_policy_changes
+        .retain(|p| p.change != PolicyChange::AddDelegate { delegate });
+    let dropped_queued = vault.pending_policy_changes.len() < queued;
     if let Some(i) = vault.delegates.iter().position(|d| *d == delegate) {
         vault.delegates.swap_remove(i);
         Ok(())
+    } else if dropped_queued {
+        Ok(())
     } else {
         err!(ErrorCode::NotFound)
     }
@@ -36,9 +63,12 @@ pub struct UpdateDelegates<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
     pub vault: Account<'info, CollateralVault>,
+
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = vault_authority.bump)]
+    pub vault_authority: Account<'info, VaultAuthority>,
 }
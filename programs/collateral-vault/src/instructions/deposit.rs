@@ -1,13 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use crate::constants::{MIN_DEPOSIT, VAULT_SEED};
+use crate::constants::{MIN_DEPOSIT, TREASURY_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED};
 use crate::error::ErrorCode;
 use crate::events::{DepositEvent, TransactionEvent};
-use crate::state::CollateralVault;
+use crate::instructions::accrue_fees::charge_custody_fee;
+use crate::state::{CollateralVault, VaultAuthority};
 use crate::types::TransactionType;
 
+#[allow(clippy::manual_contains)]
 pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
     require!(amount >= MIN_DEPOSIT, ErrorCode::InvalidAmount);
 
     let user_token_account = &ctx.accounts.user_token_account;
@@ -89,6 +96,22 @@ pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
             .ok_or(ErrorCode::Overflow)?;
     }
 
+    // Protocol TVL (net deposits)
+    let va = &mut ctx.accounts.vault_authority;
+    va.total_value_locked = va
+        .total_value_locked
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // Custody fees accrued on the pre-deposit balance are collected once funds arrive
+    charge_custody_fee(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.vault_authority,
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.token_program,
+    )?;
+
     emit!(DepositEvent {
         vault: vault_key,
         owner: ctx.accounts.vault.owner,
@@ -120,17 +143,32 @@ pub struct Deposit<'info> {
     // Verify the provided vault PDA belongs to this owner
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
     pub vault: Account<'info, CollateralVault>,
 
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
 
     #[account(mut)]
     pub vault_token_account: Account<'info, TokenAccount>,
 
+    /// Protocol treasury for the vault's mint; receives custody fees
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, vault.usdt_mint.as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
     pub token_program: Program<'info, Token>,
 }
@@ -1,27 +1,36 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use crate::constants::{VAULT_AUTHORITY_SEED, VAULT_SEED};
+use crate::constants::{TREASURY_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED};
 use crate::error::ErrorCode;
 use crate::events::{EmergencyWithdrawEvent, TransactionEvent};
+use crate::instructions::co_signer::enforce_co_signer;
+use crate::instructions::treasury::collect_fee;
 use crate::state::{CollateralVault, VaultAuthority};
 use crate::types::TransactionType;
 
 pub fn handler(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
     require!(amount > 0, ErrorCode::InvalidAmount);
 
     let authority_key = ctx.accounts.authority.key();
-    let owner_key = ctx.accounts.owner.key();
     let governance = ctx.accounts.vault_authority.governance;
 
     let vault_bump = ctx.accounts.vault.bump;
     let vault_key = ctx.accounts.vault.key();
     let usdt_mint = ctx.accounts.vault.usdt_mint;
 
-    // Authorization: only owner or governance
+    // Authorization: governance, or the owner (multisig threshold when enabled)
     let is_governance = authority_key == governance;
-    let is_owner = authority_key == owner_key;
-    require!(is_governance || is_owner, ErrorCode::Unauthorized);
+    if !is_governance {
+        ctx.accounts
+            .vault
+            .check_owner_authority(authority_key, ctx.remaining_accounts)?;
+    }
 
     // Token account checks
     require_keys_eq!(
@@ -45,6 +54,19 @@ pub fn handler(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
         ErrorCode::Unauthorized
     );
 
+    // The owner path needs the co-signer for amounts above its threshold
+    if !is_governance {
+        enforce_co_signer(
+            &mut ctx.accounts.vault,
+            vault_key,
+            amount,
+            ctx.accounts.user_token_account.key(),
+            authority_key,
+            ctx.remaining_accounts,
+            &ctx.accounts.instructions,
+        )?;
+    }
+
     // Explicitly assert token program owners of token accounts
     require_keys_eq!(
         *ctx.accounts.user_token_account.to_account_info().owner,
@@ -116,10 +138,22 @@ pub fn handler(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
         }
     }
 
-    // PDA signer seeds: ["vault", owner]
-    let signer_seeds: &[&[u8]] = &[VAULT_SEED, owner_key.as_ref(), &[vault_bump]];
+    // Protocol TVL (net deposits)
+    let va = &mut ctx.accounts.vault_authority;
+    va.total_value_locked = va.total_value_locked.saturating_sub(amount);
+
+    // PDA signer seeds: ["vault", original_owner]
+    let vault_seed = ctx.accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[vault_bump]];
     let signer: &[&[&[u8]]] = &[signer_seeds];
 
+    // Emergency fee is taken out of the amount; the vault is debited the full amount
+    let fee = ctx
+        .accounts
+        .vault_authority
+        .emergency_withdraw_fee
+        .fee_for(amount);
+
     // Transfer tokens: vault -> user
     let cpi_accounts = anchor_spl::token::Transfer {
         from: ctx.accounts.vault_token_account.to_account_info(),
@@ -131,13 +165,22 @@ pub fn handler(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
         cpi_accounts,
         signer,
     );
-    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    anchor_spl::token::transfer(cpi_ctx, amount - fee)?;
+    collect_fee(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.vault_token_account.to_account_info(),
+        ctx.accounts.treasury_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        signer,
+        fee,
+    )?;
 
     emit!(EmergencyWithdrawEvent {
         vault: vault_key,
         owner: ctx.accounts.vault.owner,
         authority: authority_key,
         amount,
+        fee,
         new_total_balance: ctx.accounts.vault.total_balance,
         new_available_balance: ctx.accounts.vault.available_balance,
         new_locked_balance: ctx.accounts.vault.locked_balance,
@@ -165,13 +208,15 @@ pub struct EmergencyWithdraw<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
 
     #[account(
+        mut,
         seeds = [VAULT_AUTHORITY_SEED],
         bump = vault_authority.bump,
     )]
@@ -183,5 +228,17 @@ pub struct EmergencyWithdraw<'info> {
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
 
+    /// Protocol treasury for the vault's mint; receives the emergency withdrawal fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, vault.usdt_mint.as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
     pub token_program: Program<'info, Token>,
+
+    /// CHECK: Instructions sysvar, read for ed25519 co-signer approvals
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
 }
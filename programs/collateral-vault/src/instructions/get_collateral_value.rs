@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::constants::COLLATERAL_CONFIG_SEED;
+use crate::error::ErrorCode;
+use crate::oracle::{apply_haircut, PriceFeed};
+use crate::state::{CollateralConfig, CollateralVault};
+use crate::types::CollateralValuation;
+
+// Read-only valuation; the result is returned to the caller via return data
+pub fn handler(ctx: Context<GetCollateralValue>) -> Result<CollateralValuation> {
+    let config = &ctx.accounts.collateral_config;
+    let vault = &ctx.accounts.vault;
+
+    let feed = {
+        let data = ctx.accounts.price_feed.try_borrow_data()?;
+        PriceFeed::unpack(&data)?
+    };
+    let now = Clock::get()?.unix_timestamp;
+    feed.validate(now, config.max_staleness_seconds, config.max_confidence_bps)?;
+
+    let amount = vault.total_balance;
+    let usd_value = feed.usd_value(amount, ctx.accounts.mint.decimals)?;
+    let adjusted_usd_value = apply_haircut(usd_value, config.haircut_bps)?;
+
+    Ok(CollateralValuation {
+        vault: vault.key(),
+        mint: vault.usdt_mint,
+        amount,
+        price: feed.price,
+        expo: feed.expo,
+        publish_time: feed.publish_time,
+        usd_value,
+        haircut_bps: config.haircut_bps,
+        adjusted_usd_value,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetCollateralValue<'info> {
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(address = vault.usdt_mint @ ErrorCode::Unauthorized)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [COLLATERAL_CONFIG_SEED, vault.usdt_mint.as_ref()],
+        bump = collateral_config.bump,
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// CHECK: pinned to the configured feed; layout validated on read
+    #[account(address = collateral_config.price_feed @ ErrorCode::InvalidOracleAccount)]
+    pub price_feed: UncheckedAccount<'info>,
+}
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::constants::{
+    BPS_DENOMINATOR, SECONDS_PER_YEAR, VAULT_SEED, YIELD_POSITION_SEED, YIELD_STRATEGY_SEED,
+};
+use crate::error::ErrorCode;
+use crate::events::{YieldHarvestedEvent, YieldLossEvent};
+use crate::state::{CollateralVault, VaultAuthority, YieldPosition, YieldStrategy};
+use crate::yield_adapter::{query_position_value, AdapterAccounts};
+
+/// Mark one of the vault's yield positions to market. The yield program reports
+/// the position value; only the part above what is already booked for that
+/// position (`deposited + accrued`) is credited to the position and to
+/// `yield_accrued_balance`, so repeated harvests never double-count. A value
+/// below the booked amount writes off accrued yield first, then principal.
+///
+/// Permissionless: the value comes from a whitelisted program and no tokens move.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, HarvestYield<'info>>) -> Result<()> {
+    let yp = ctx.accounts.yield_program.key();
+    require!(
+        ctx.accounts.vault_authority.yield_whitelist.contains(&yp),
+        ErrorCode::YieldProgramNotWhitelisted
+    );
+
+    let vault_before = ctx.accounts.vault_token_account.amount;
+    let receipts_before = ctx.accounts.receipt_token_account.amount;
+
+    let vault_seed = ctx.accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    let position_value = query_position_value(
+        &ctx.accounts.yield_program.to_account_info(),
+        AdapterAccounts {
+            vault: ctx.accounts.vault.to_account_info(),
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            receipt_token_account: ctx.accounts.receipt_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        ctx.remaining_accounts,
+        signer,
+    )?;
+
+    // The view must not move tokens
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.receipt_token_account.reload()?;
+    require!(
+        ctx.accounts.vault_token_account.amount == vault_before
+            && ctx.accounts.receipt_token_account.amount == receipts_before,
+        ErrorCode::YieldAdapterMismatch
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let position = &mut ctx.accounts.yield_position;
+    let vault = &mut ctx.accounts.vault;
+
+    let booked = position
+        .deposited
+        .checked_add(position.accrued)
+        .ok_or(ErrorCode::Overflow)?;
+    let gain = position_value.saturating_sub(booked);
+    // A position worth less than booked writes off accrued yield, then principal
+    let (principal_loss, accrued_written_off) = if position_value < booked {
+        position.write_down(vault, booked - position_value, true)?
+    } else {
+        (0, 0)
+    };
+    YieldStrategy::record_flows(&ctx.accounts.yield_strategy, 0, principal_loss)?;
+    if gain > 0 {
+        position.accrued = position
+            .accrued
+            .checked_add(gain)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.yield_accrued_balance = vault
+            .yield_accrued_balance
+            .checked_add(gain)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Period since the last mark; fall back to the last compound, then vault creation
+    let period_start = [
+        position.last_harvested_at,
+        vault.last_compounded_at,
+        vault.created_at,
+    ]
+    .into_iter()
+    .find(|t| *t > 0)
+    .unwrap_or(now);
+    let period_seconds = now.saturating_sub(period_start);
+    let realized_apr_bps = realized_apr_bps(gain, position.deposited, period_seconds);
+    position.last_harvested_at = now;
+    vault.last_harvested_at = now;
+
+    if principal_loss > 0 || accrued_written_off > 0 {
+        // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+        vault.check_balance_invariant()?;
+        emit!(YieldLossEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            program: yp,
+            principal_loss,
+            accrued_written_off,
+            new_yield_balance: vault.yield_deposited_balance,
+            new_total_balance: vault.total_balance,
+            timestamp: now,
+        });
+    }
+
+    emit!(YieldHarvestedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program: yp,
+        position_value,
+        gain,
+        new_accrued_balance: vault.yield_accrued_balance,
+        period_seconds,
+        realized_apr_bps,
+        harvested_at: now,
+    });
+
+    Ok(())
+}
+
+/// Annualized return of `gain` on `principal` over `period_seconds`, in bps.
+/// Returns 0 when there is no principal or no elapsed time.
+pub fn realized_apr_bps(gain: u64, principal: u64, period_seconds: i64) -> u64 {
+    if principal == 0 || period_seconds <= 0 {
+        return 0;
+    }
+    let numerator = (gain as u128)
+        .saturating_mul(BPS_DENOMINATOR as u128)
+        .saturating_mul(SECONDS_PER_YEAR as u128);
+    let denominator = (principal as u128).saturating_mul(period_seconds as u128);
+    u64::try_from(numerator / denominator).unwrap_or(u64::MAX)
+}
+
+#[derive(Accounts)]
+pub struct HarvestYield<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    /// Vault authority for whitelist checks
+    #[account(
+        seeds = [crate::constants::VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// The yield program holding the position
+    /// CHECK: key checked against yield_whitelist; must be executable
+    pub yield_program: UncheckedAccount<'info>,
+
+    /// Strategy record for cap accounting (may be uninitialized)
+    /// CHECK: address fixed by seeds; updated via YieldStrategy::record_flows
+    #[account(mut, seeds = [YIELD_STRATEGY_SEED, yield_program.key().as_ref()], bump)]
+    pub yield_strategy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Vault-owned token account holding the strategy's receipt tokens
+    #[account(
+        mut,
+        constraint = receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = receipt_token_account.key() != vault_token_account.key() @ ErrorCode::Unauthorized,
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump = yield_position.bump,
+        constraint = yield_position.receipt_token_account == receipt_token_account.key() @ ErrorCode::YieldAdapterMismatch,
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+
+    pub token_program: Program<'info, Token>,
+}
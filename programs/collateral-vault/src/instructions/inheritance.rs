@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::VAULT_SEED;
+use crate::error::ErrorCode;
+use crate::events::{BeneficiarySetEvent, InheritanceClaimedEvent};
+use crate::instructions::withdraw_policy::UpdatePolicy;
+use crate::state::CollateralVault;
+
+/// Name (or with `None`, remove) the beneficiary and how long the owner must
+/// stay inactive before it can inherit the vault.
+pub fn set_beneficiary(
+    ctx: Context<UpdatePolicy>,
+    beneficiary: Option<Pubkey>,
+    inactivity_period_seconds: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    vault.record_owner_activity(ctx.accounts.owner.key(), &[], now);
+    vault.set_beneficiary(beneficiary, inactivity_period_seconds)?;
+    emit!(BeneficiarySetEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        beneficiary,
+        inactivity_period_seconds: vault.inactivity_period_seconds,
+    });
+    Ok(())
+}
+
+/// The beneficiary takes ownership once the owner has been inactive for the
+/// full inactivity period.
+pub fn claim_inheritance(ctx: Context<ClaimInheritance>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    require!(
+        vault.beneficiary == Some(ctx.accounts.beneficiary.key()),
+        ErrorCode::Unauthorized
+    );
+    let old_owner = vault.claim_inheritance(now)?;
+    emit!(InheritanceClaimedEvent {
+        vault: vault.key(),
+        old_owner,
+        new_owner: vault.owner,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimInheritance<'info> {
+    pub beneficiary: Signer<'info>,
+
+    /// Current owner, to check against the vault; need not sign
+    /// CHECK: equality check only
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+}
@@ -12,6 +12,7 @@ pub fn handler(ctx: Context<InitializeVault>) -> Result<()> {
 
     // Initialize vault state
     vault.owner = ctx.accounts.user.key();
+    vault.original_owner = ctx.accounts.user.key();
     vault.token_account = ctx.accounts.vault_token_account.key();
     vault.usdt_mint = ctx.accounts.usdt_mint.key();
     vault.total_balance = 0;
@@ -25,11 +26,13 @@ pub fn handler(ctx: Context<InitializeVault>) -> Result<()> {
     vault.multisig_signers.clear();
     vault.delegates.clear();
     vault.timelocks.clear();
+    vault.last_owner_activity_at = vault.created_at;
+    vault.layout_version = ACCOUNT_LAYOUT_VERSION;
 
     Ok(())
 }
 
-use crate::constants::VAULT_SEED;
+use crate::constants::{ACCOUNT_LAYOUT_VERSION, VAULT_SEED};
 
 #[derive(Accounts)]
 pub struct InitializeVault<'info> {
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{INTEGRATOR_SEED, MAX_INTEGRATOR_NAME_LEN, VAULT_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::{IntegratorRegisteredEvent, IntegratorUpdatedEvent, RiskSignerSetEvent};
+use crate::state::integrator_registry::CAP_ALL;
+use crate::state::{IntegratorRegistry, VaultAuthority};
+
+/// Register `program` as an integrator with the given capabilities and
+/// protocol-wide exposure cap. The record starts enabled.
+pub fn register_integrator(
+    ctx: Context<RegisterIntegrator>,
+    program: Pubkey,
+    capabilities: u8,
+    max_total_locked: Option<u64>,
+    name: String,
+    version: u32,
+) -> Result<()> {
+    require!(capabilities & !CAP_ALL == 0, ErrorCode::InvalidCapabilities);
+    require!(
+        name.len() <= MAX_INTEGRATOR_NAME_LEN,
+        ErrorCode::NameTooLong
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let integrator = &mut ctx.accounts.integrator;
+    integrator.program = program;
+    integrator.capabilities = capabilities;
+    integrator.enabled = true;
+    integrator.max_total_locked = max_total_locked;
+    integrator.total_locked = 0;
+    integrator.name = name.clone();
+    integrator.version = version;
+    integrator.registered_at = now;
+    integrator.updated_at = now;
+    integrator.deprecated_at = 0;
+    integrator.grace_period_ends_at = 0;
+    integrator.risk_signer = None;
+    integrator.bump = ctx.bumps.integrator;
+
+    emit!(IntegratorRegisteredEvent {
+        program,
+        capabilities,
+        max_total_locked,
+        name,
+        version,
+    });
+    Ok(())
+}
+
+/// Change an integrator's capabilities, status, cap or metadata. Lowering the
+/// cap below current exposure only blocks new locks.
+pub fn update_integrator(
+    ctx: Context<UpdateIntegrator>,
+    capabilities: u8,
+    enabled: bool,
+    max_total_locked: Option<u64>,
+    name: String,
+    version: u32,
+) -> Result<()> {
+    require!(capabilities & !CAP_ALL == 0, ErrorCode::InvalidCapabilities);
+    require!(
+        name.len() <= MAX_INTEGRATOR_NAME_LEN,
+        ErrorCode::NameTooLong
+    );
+
+    let integrator = &mut ctx.accounts.integrator;
+    integrator.capabilities = capabilities;
+    integrator.enabled = enabled;
+    integrator.max_total_locked = max_total_locked;
+    integrator.name = name;
+    integrator.version = version;
+    integrator.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(IntegratorUpdatedEvent {
+        program: integrator.program,
+        capabilities,
+        enabled,
+        max_total_locked,
+        version,
+    });
+    Ok(())
+}
+
+/// Name (or clear) the key whose ed25519-signed risk attestations `withdraw`
+/// accepts in place of this integrator's on-chain position summary.
+pub fn set_risk_signer(ctx: Context<UpdateIntegrator>, risk_signer: Option<Pubkey>) -> Result<()> {
+    let integrator = &mut ctx.accounts.integrator;
+    integrator.risk_signer = risk_signer;
+    integrator.updated_at = Clock::get()?.unix_timestamp;
+    emit!(RiskSignerSetEvent {
+        program: integrator.program,
+        risk_signer,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(program: Pubkey)]
+pub struct RegisterIntegrator<'info> {
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    #[account(
+        init,
+        payer = governance,
+        space = IntegratorRegistry::LEN,
+        seeds = [INTEGRATOR_SEED, program.as_ref()],
+        bump
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateIntegrator<'info> {
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    #[account(
+        mut,
+        seeds = [INTEGRATOR_SEED, integrator.program.as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_VAULT_INTEGRATORS;
+use crate::error::ErrorCode;
+use crate::events::{IntegratorApprovedEvent, IntegratorRevokedEvent};
+use crate::instructions::withdraw_policy::{RestrictPolicy, UpdatePolicy};
+use crate::types::IntegratorApproval;
+
+/// Allow an authorized program to lock and transfer this vault's collateral,
+/// optionally capping how much it may hold locked. Re-approving updates the cap.
+pub fn approve_integrator(
+    ctx: Context<UpdatePolicy>,
+    program: Pubkey,
+    max_locked: Option<u64>,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    let vault = &mut ctx.accounts.vault;
+    if let Some(entry) = vault.integrators.iter_mut().find(|i| i.program == program) {
+        entry.max_locked = max_locked;
+    } else {
+        require!(
+            vault.integrators.len() < MAX_VAULT_INTEGRATORS,
+            ErrorCode::Overflow
+        );
+        vault.integrators.push(IntegratorApproval {
+            program,
+            max_locked,
+            locked: 0,
+            lease_expires_at: 0,
+            attestation_nonce: 0,
+        });
+    }
+    emit!(IntegratorApprovedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program,
+        max_locked,
+    });
+    Ok(())
+}
+
+/// Withdraw an integrator's approval once it holds no locks on this vault.
+pub fn revoke_integrator(ctx: Context<RestrictPolicy>, program: Pubkey) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    let vault = &mut ctx.accounts.vault;
+    let index = vault
+        .integrators
+        .iter()
+        .position(|i| i.program == program)
+        .ok_or(ErrorCode::NotFound)?;
+    require!(
+        vault.integrators[index].locked == 0,
+        ErrorCode::IntegratorHasLocks
+    );
+    vault.integrators.swap_remove(index);
+    emit!(IntegratorRevokedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program,
+    });
+    Ok(())
+}
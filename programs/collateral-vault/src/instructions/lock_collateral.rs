@@ -1,13 +1,25 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::VAULT_AUTHORITY_SEED;
+use crate::constants::{INTEGRATOR_SEED, VAULT_AUTHORITY_SEED};
 use crate::error::ErrorCode;
-use crate::events::{LockEvent, TransactionEvent};
-use crate::state::{CollateralVault, VaultAuthority};
+use crate::events::{
+    CustodyFeeChargedEvent, LockEvent, LockLeaseRenewedEvent, TransactionEvent,
+    YieldPullbackRequiredEvent,
+};
+use crate::state::integrator_registry::CAP_LOCK;
+use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
 use crate::types::TransactionType;
 use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
 
-pub fn handler(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
+/// Lock `amount` of the vault's available collateral for the calling integrator.
+/// With `lease_seconds` set, everything the integrator holds locked in this vault
+/// is leased until now + `lease_seconds`; it must `renew_lock` before then or the
+/// owner can release the lock once the governance grace period has also passed.
+pub fn handler(
+    ctx: Context<LockCollateral>,
+    amount: u64,
+    lease_seconds: Option<i64>,
+) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
 
     let va = &ctx.accounts.vault_authority;
@@ -17,11 +29,10 @@ pub fn handler(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
     // Resolve the actual caller program from the instructions sysvar
     let actual_caller = resolve_caller_program(&ctx.accounts.instructions)?;
 
-    // Authorization: caller program must be in the allowlist
-    require!(
-        va.authorized_programs.iter().any(|p| *p == actual_caller),
-        ErrorCode::UnauthorizedProgram
-    );
+    // Authorization: caller program must be registered, enabled and hold CAP_LOCK
+    ctx.accounts
+        .integrator
+        .require_capability(actual_caller, CAP_LOCK)?;
 
     // Optional CPI-origin enforcement: ensure the declared caller matches the actual caller
     if va.cpi_enforced {
@@ -32,11 +43,42 @@ pub fn handler(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
         );
     }
 
+    let risk_params = va.risk_params_for(ctx.accounts.vault.risk_tier_override);
+
     let vault = &mut ctx.accounts.vault;
+
+    // Custody fees accrue here; no token accounts are passed, so they are
+    // collected on the vault's next deposit, withdraw or accrue_fees
+    let now = Clock::get()?.unix_timestamp;
+    let accrued_fee = vault.accrue_custody_fee(va, now)?;
+    if accrued_fee > 0 {
+        emit!(CustodyFeeChargedEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            accrued: accrued_fee,
+            collected: 0,
+            owed: vault.custody_fee_owed,
+            timestamp: now,
+        });
+    }
+
     require!(
         vault.available_balance >= amount,
         ErrorCode::InsufficientFunds
     );
+    // Per-vault opt-in and cap, then the integrator's protocol-wide cap
+    vault.record_integrator_lock(actual_caller, amount)?;
+    ctx.accounts.integrator.record_lock(amount)?;
+    if let Some(lease_seconds) = lease_seconds {
+        let lease_expires_at = vault.renew_integrator_lease(actual_caller, now, lease_seconds)?;
+        emit!(LockLeaseRenewedEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            program: actual_caller,
+            lease_expires_at,
+            releasable_at: lease_expires_at.saturating_add(va.lock_lease_grace_seconds),
+        });
+    }
 
     vault.locked_balance = vault
         .locked_balance
@@ -47,15 +89,34 @@ pub fn handler(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
         .checked_sub(amount)
         .ok_or(ErrorCode::Overflow)?;
 
-    // Invariant: total = locked + available
-    require!(
-        vault.total_balance
-            == vault
-                .locked_balance
-                .checked_add(vault.available_balance)
-                .ok_or(ErrorCode::Overflow)?,
-        ErrorCode::InvariantViolation
-    );
+    // Risk limits: locked share and free buffer after this lock
+    if let Some(params) = risk_params {
+        params.check_lock(
+            vault.total_balance,
+            vault.locked_balance,
+            vault.available_balance,
+        )?;
+    }
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    // Liquidity: a lock that dips below the reserve requests a yield pull-back
+    let reserve_target = vault.liquid_reserve_target();
+    if vault.available_balance < reserve_target && vault.yield_deposited_balance > 0 {
+        let pullback = core::cmp::min(
+            reserve_target - vault.available_balance,
+            vault.yield_deposited_balance,
+        );
+        vault.yield_pullback_pending = pullback;
+        emit!(YieldPullbackRequiredEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            reserve_target,
+            available_balance: vault.available_balance,
+            pullback_amount: pullback,
+        });
+    }
 
     emit!(LockEvent {
         vault: vault.key(),
@@ -70,7 +131,7 @@ pub fn handler(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
         owner: vault.owner,
         transaction_type: TransactionType::Lock,
         amount,
-        timestamp: Clock::get()?.unix_timestamp,
+        timestamp: now,
     });
 
     Ok(())
@@ -78,7 +139,7 @@ pub fn handler(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct LockCollateral<'info> {
-    /// CHECK: program id of the calling program, used for allowlist verification
+    /// CHECK: program id of the calling program, used for registry lookup
     pub caller_program: UncheckedAccount<'info>,
 
     #[account(
@@ -87,6 +148,14 @@ pub struct LockCollateral<'info> {
     )]
     pub vault_authority: Account<'info, VaultAuthority>,
 
+    /// Registry record of the calling program; checked against the actual caller
+    #[account(
+        mut,
+        seeds = [INTEGRATOR_SEED, caller_program.key().as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
     /// CHECK: Instructions sysvar account for CPI-origin verification when enforced
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions: AccountInfo<'info>,
@@ -95,7 +164,9 @@ pub struct LockCollateral<'info> {
     pub vault: Account<'info, CollateralVault>,
 }
 
-fn resolve_caller_program(instructions: &AccountInfo<'_>) -> Result<Pubkey> {
+/// Program id of the instruction that invoked this one, read from the
+/// instructions sysvar. Integrator CPIs are authorized against this id.
+pub(crate) fn resolve_caller_program(instructions: &AccountInfo<'_>) -> Result<Pubkey> {
     let current_index = sysvar_instructions::load_current_index_checked(instructions)?;
     require!(current_index > 0, ErrorCode::UnauthorizedProgram);
     let caller_ix = sysvar_instructions::load_instruction_at_checked(
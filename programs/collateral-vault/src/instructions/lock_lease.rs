@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    INTEGRATOR_SEED, LOCK_LEASE_WARNING_SECONDS, VAULT_AUTHORITY_SEED, VAULT_SEED,
+};
+use crate::error::ErrorCode;
+use crate::events::{ExpiredLockReleasedEvent, LockLeaseExpiringEvent, LockLeaseRenewedEvent};
+use crate::instructions::lock_collateral::resolve_caller_program;
+use crate::state::integrator_registry::CAP_LOCK;
+use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
+
+/// CPI keep-alive: the calling integrator extends its lease on the locks it
+/// holds in this vault to now + `lease_seconds`.
+pub fn renew_lock(ctx: Context<RenewLock>, lease_seconds: i64) -> Result<()> {
+    let va = &ctx.accounts.vault_authority;
+    require!(!va.freeze, ErrorCode::Frozen);
+
+    let actual_caller = resolve_caller_program(&ctx.accounts.instructions)?;
+    ctx.accounts
+        .integrator
+        .require_capability(actual_caller, CAP_LOCK)?;
+    if va.cpi_enforced {
+        require_keys_eq!(
+            ctx.accounts.caller_program.key(),
+            actual_caller,
+            ErrorCode::UnauthorizedProgram
+        );
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    let lease_expires_at = vault.renew_integrator_lease(actual_caller, now, lease_seconds)?;
+
+    emit!(LockLeaseRenewedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program: actual_caller,
+        lease_expires_at,
+        releasable_at: lease_expires_at.saturating_add(va.lock_lease_grace_seconds),
+    });
+    Ok(())
+}
+
+/// Owner self-service: once an integrator's lease has lapsed by more than the
+/// governance grace period, release everything it holds locked in the vault.
+pub fn release_expired_lock(ctx: Context<ReleaseExpiredLock>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault
+        .record_owner_activity(ctx.accounts.owner.key(), &[], now);
+    let grace = ctx.accounts.vault_authority.lock_lease_grace_seconds;
+    let program = ctx.accounts.integrator.program;
+    let vault = &mut ctx.accounts.vault;
+
+    let lease_expires_at = vault
+        .integrators
+        .iter()
+        .find(|i| i.program == program)
+        .map(|i| i.lease_expires_at)
+        .ok_or(ErrorCode::NotFound)?;
+    require!(
+        lease_expires_at != 0 && now >= lease_expires_at.saturating_add(grace),
+        ErrorCode::LockLeaseActive
+    );
+
+    let amount = vault.release_integrator_lock(program)?;
+    ctx.accounts.integrator.record_unlock(amount);
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    emit!(ExpiredLockReleasedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program,
+        amount,
+        lease_expires_at,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Permissionless keeper check: emits `LockLeaseExpiringEvent` when `program`'s
+/// lease on this vault expires within `LOCK_LEASE_WARNING_SECONDS` or already
+/// has, so keepers can alert the integrator before the owner may release.
+pub fn check_lock_lease(ctx: Context<CheckLockLease>, program: Pubkey) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &ctx.accounts.vault;
+    let entry = vault
+        .integrators
+        .iter()
+        .find(|i| i.program == program)
+        .ok_or(ErrorCode::NotFound)?;
+    require!(
+        entry.locked > 0
+            && entry.lease_expires_at != 0
+            && now
+                >= entry
+                    .lease_expires_at
+                    .saturating_sub(LOCK_LEASE_WARNING_SECONDS),
+        ErrorCode::LockLeaseNotExpiring
+    );
+
+    emit!(LockLeaseExpiringEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program,
+        locked: entry.locked,
+        lease_expires_at: entry.lease_expires_at,
+        releasable_at: entry
+            .lease_expires_at
+            .saturating_add(ctx.accounts.vault_authority.lock_lease_grace_seconds),
+        expired: now >= entry.lease_expires_at,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RenewLock<'info> {
+    /// CHECK: program id of the calling program, used for registry lookup
+    pub caller_program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// Registry record of the calling program; checked against the actual caller
+    #[account(
+        seeds = [INTEGRATOR_SEED, caller_program.key().as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
+    /// CHECK: Instructions sysvar account for CPI-origin verification when enforced
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub vault: Account<'info, CollateralVault>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseExpiredLock<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    #[account(
+        mut,
+        seeds = [INTEGRATOR_SEED, integrator.program.as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct CheckLockLease<'info> {
+    #[account(
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+}
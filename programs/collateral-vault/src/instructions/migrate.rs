@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::system_program;
+
+use crate::constants::{VAULT_AUTHORITY_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+use crate::events::{VaultAuthorityMigratedEvent, VaultMigratedEvent};
+use crate::state::{CollateralVault, LegacyCollateralVault, LegacyVaultAuthority, VaultAuthority};
+
+// A version-1 vault must reach its new size in a single resize
+const _: () =
+    assert!(CollateralVault::LEN - LegacyCollateralVault::LEN <= MAX_PERMITTED_DATA_INCREASE);
+
+/// Rewrite the version-1 vault authority in the current layout. Governance runs
+/// this once after the program upgrade, before anything else touches the account.
+pub fn migrate_vault_authority(ctx: Context<MigrateVaultAuthority>) -> Result<()> {
+    let info = ctx.accounts.vault_authority.to_account_info();
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        LegacyVaultAuthority::try_from_data(&data).ok_or(ErrorCode::AccountAlreadyMigrated)?
+    };
+    require_keys_eq!(
+        legacy.governance,
+        ctx.accounts.governance.key(),
+        ErrorCode::Unauthorized
+    );
+    let dropped_authorized_programs = legacy.authorized_programs.clone();
+    let upgraded = legacy.upgrade();
+    write_account(
+        &info,
+        &upgraded,
+        VaultAuthority::LEN,
+        &ctx.accounts.governance,
+        &ctx.accounts.system_program,
+    )?;
+    emit!(VaultAuthorityMigratedEvent {
+        governance: upgraded.governance,
+        layout_version: upgraded.layout_version,
+        dropped_authorized_programs,
+    });
+    Ok(())
+}
+
+/// Rewrite a version-1 vault in the current layout. Anyone may submit it and
+/// pays the extra rent. The vault's net deposits predate the protocol TVL
+/// counter, so they are credited to it here; the vault authority must
+/// therefore be migrated first.
+pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+    let info = ctx.accounts.vault.to_account_info();
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        LegacyCollateralVault::try_from_data(&data).ok_or(ErrorCode::AccountAlreadyMigrated)?
+    };
+    // Version-1 vaults were seeded by their owner; the stored bump must re-derive this address
+    let expected = Pubkey::create_program_address(
+        &[VAULT_SEED, legacy.owner.as_ref(), &[legacy.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::Unauthorized)?;
+    require_keys_eq!(expected, info.key(), ErrorCode::Unauthorized);
+
+    let upgraded = legacy.upgrade(Clock::get()?.unix_timestamp);
+    let va = &mut ctx.accounts.vault_authority;
+    va.total_value_locked = va
+        .total_value_locked
+        .checked_add(
+            upgraded
+                .total_deposited
+                .saturating_sub(upgraded.total_withdrawn),
+        )
+        .ok_or(ErrorCode::Overflow)?;
+    write_account(
+        &info,
+        &upgraded,
+        CollateralVault::LEN,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    emit!(VaultMigratedEvent {
+        vault: info.key(),
+        owner: upgraded.owner,
+        layout_version: upgraded.layout_version,
+    });
+    Ok(())
+}
+
+/// Resize `info` to `len`, settle rent with `payer` and write `account` over it.
+fn write_account<'info, T: AccountSerialize>(
+    info: &AccountInfo<'info>,
+    account: &T,
+    len: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(len);
+    let lamports = info.lamports();
+    if rent > lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    } else if lamports > rent {
+        // A smaller layout frees rent; hand it back to the payer
+        **info.try_borrow_mut_lamports()? -= lamports - rent;
+        **payer.to_account_info().try_borrow_mut_lamports()? += lamports - rent;
+    }
+    info.resize(len)?;
+    let mut data = info.try_borrow_mut_data()?;
+    data.fill(0);
+    account.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateVaultAuthority<'info> {
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    /// Version-1 vault authority, decoded by hand since it no longer matches `VaultAuthority`
+    /// CHECK: address fixed by seeds; discriminator and size checked in the handler
+    #[account(mut, seeds = [VAULT_AUTHORITY_SEED], bump, owner = crate::ID)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Version-1 vault, decoded by hand since it no longer matches `CollateralVault`
+    /// CHECK: discriminator, size and PDA derivation checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [VAULT_AUTHORITY_SEED], bump = vault_authority.bump)]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -25,6 +25,8 @@ pub mod multisig;
 pub use multisig::*;
 pub mod transfer_collateral;
 pub use transfer_collateral::*;
+pub mod batch_settle;
+pub use batch_settle::*;
 
 pub mod delegation;
 pub use delegation::*;
@@ -35,6 +37,10 @@ pub mod close_vault;
 pub use close_vault::*;
 pub mod get_vault_info;
 pub use get_vault_info::*;
+pub mod collateral_config;
+pub use collateral_config::*;
+pub mod get_collateral_value;
+pub use get_collateral_value::*;
 
 pub mod emergency_withdraw;
 pub use emergency_withdraw::*;
@@ -45,3 +51,35 @@ pub mod yield_withdraw;
 pub use yield_withdraw::*;
 pub mod compound_yield;
 pub use compound_yield::*;
+pub mod harvest_yield;
+pub use harvest_yield::*;
+pub mod rebalance_yield;
+pub use rebalance_yield::*;
+pub mod yield_redemption;
+pub use yield_redemption::*;
+pub mod crank_compound;
+pub use crank_compound::*;
+pub mod treasury;
+pub use treasury::*;
+pub mod accrue_fees;
+pub use accrue_fees::*;
+pub mod integrators;
+pub use integrators::*;
+pub mod integrator_registry;
+pub use integrator_registry::*;
+pub mod wind_down;
+pub use wind_down::*;
+pub mod lock_lease;
+pub use lock_lease::*;
+pub mod co_signer;
+pub use co_signer::*;
+pub mod policy_changes;
+pub use policy_changes::*;
+pub mod panic_lock;
+pub use panic_lock::*;
+pub mod recovery;
+pub use recovery::*;
+pub mod inheritance;
+pub use inheritance::*;
+pub mod migrate;
+pub use migrate::*;
@@ -1,16 +1,24 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::{MAX_MULTISIG_SIGNERS, VAULT_SEED},
+    constants::{MAX_MULTISIG_SIGNERS, VAULT_AUTHORITY_SEED, VAULT_SEED},
     error::ErrorCode,
-    state::CollateralVault,
+    instructions::policy_changes::submit_policy_change,
+    state::{CollateralVault, VaultAuthority},
+    types::PolicyChange,
 };
 
+#[allow(clippy::unnecessary_cast)]
 pub fn set_vault_multisig(
     ctx: Context<SetVaultMultisig>,
     signers: Vec<Pubkey>,
     threshold: u8,
 ) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
     // Validate inputs
     require!(
         (threshold as usize) <= signers.len(),
@@ -28,18 +36,28 @@ pub fn set_vault_multisig(
     }
     require!(uniq.len() == signers.len(), ErrorCode::InvalidAmount);
 
-    let vault = &mut ctx.accounts.vault;
-    vault.multisig_threshold = threshold;
-    vault.multisig_signers = signers;
-
-    Ok(())
+    // Lowering the threshold or handing a quorum to new keys is delayed
+    submit_policy_change(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_authority,
+        PolicyChange::Multisig { signers, threshold },
+    )
 }
 
 pub fn disable_vault_multisig(ctx: Context<SetVaultMultisig>) -> Result<()> {
-    let vault = &mut ctx.accounts.vault;
-    vault.multisig_threshold = 0;
-    vault.multisig_signers.clear();
-    Ok(())
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    submit_policy_change(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_authority,
+        PolicyChange::Multisig {
+            signers: vec![],
+            threshold: 0,
+        },
+    )
 }
 
 #[derive(Accounts)]
@@ -49,9 +67,13 @@ pub struct SetVaultMultisig<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
+
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = vault_authority.bump)]
+    pub vault_authority: Account<'info, VaultAuthority>,
 }
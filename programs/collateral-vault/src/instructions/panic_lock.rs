@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::VAULT_SEED;
+use crate::error::ErrorCode;
+use crate::events::{PanicUnlockRequestedEvent, VaultPanicLockedEvent, VaultPanicUnlockedEvent};
+use crate::state::CollateralVault;
+
+/// Freeze the vault's outflows and policy changes at once. The owner, any
+/// delegate or any single multisig signer may pull it.
+pub fn panic_lock_vault(ctx: Context<PanicLock>) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    let by = ctx.accounts.authority.key();
+    let vault = &mut ctx.accounts.vault;
+    require!(vault.can_panic_lock(&by), ErrorCode::Unauthorized);
+    let now = Clock::get()?.unix_timestamp;
+    vault.panic_lock(now);
+    emit!(VaultPanicLockedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        by,
+        locked_at: vault.panic_locked_at,
+    });
+    Ok(())
+}
+
+/// Start the `PANIC_UNLOCK_COOLDOWN_SECONDS` wait before the vault can be
+/// unlocked. Needs the owner (or multisig threshold); delegates cannot unlock.
+pub fn request_panic_unlock(ctx: Context<PanicLock>) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    ctx.accounts
+        .vault
+        .check_owner_authority(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    let unlocks_at = vault.request_panic_unlock(now)?;
+    emit!(PanicUnlockRequestedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        unlocks_at,
+    });
+    Ok(())
+}
+
+/// Lift the panic lock after the cooldown. Same authorization as the request.
+pub fn unlock_vault(ctx: Context<PanicLock>) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    ctx.accounts
+        .vault
+        .check_owner_authority(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    vault.complete_panic_unlock(now)?;
+    emit!(VaultPanicUnlockedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PanicLock<'info> {
+    pub authority: Signer<'info>,
+
+    /// Owner pubkey used for PDA derivation; need not sign
+    /// CHECK: used for seed derivation and equality check only
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_DELEGATES;
+use crate::error::ErrorCode;
+use crate::events::{
+    PolicyChangeCancelledEvent, PolicyChangeQueuedEvent, WithdrawMinDelaySetEvent,
+    WithdrawRateLimitSetEvent, WithdrawWhitelistUpdatedEvent,
+};
+use crate::instructions::withdraw_policy::{RestrictPolicy, UpdatePolicy};
+use crate::state::{CollateralVault, VaultAuthority};
+use crate::types::PolicyChange;
+
+/// Apply `change` now if it tightens the vault's protection (or no delay is
+/// configured); otherwise queue it until the policy change delay has passed.
+pub(crate) fn submit_policy_change(
+    vault: &mut Account<CollateralVault>,
+    va: &VaultAuthority,
+    change: PolicyChange,
+) -> Result<()> {
+    let delay = vault.policy_change_delay(va.policy_change_delay_floor_seconds);
+    if delay > 0 && vault.is_policy_relaxation(&change) {
+        let now = Clock::get()?.unix_timestamp;
+        let effective_at = vault.queue_policy_change(change.clone(), now, delay)?;
+        emit!(PolicyChangeQueuedEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            change,
+            effective_at,
+        });
+        return Ok(());
+    }
+    // A direct change replaces anything queued for the same setting
+    vault
+        .pending_policy_changes
+        .retain(|p| !p.change.superseded_by(&change));
+    apply_policy_change(vault, change)
+}
+
+fn apply_policy_change(vault: &mut Account<CollateralVault>, change: PolicyChange) -> Result<()> {
+    match change {
+        PolicyChange::MinWithdrawDelay { seconds } => {
+            vault.min_withdraw_delay_seconds = seconds;
+            emit!(WithdrawMinDelaySetEvent {
+                vault: vault.key(),
+                owner: vault.owner,
+                seconds
+            });
+        }
+        PolicyChange::RateLimit {
+            window_seconds,
+            max_amount,
+        } => {
+            vault.rate_window_seconds = window_seconds;
+            vault.rate_limit_amount = max_amount;
+            // Reset window counters on policy change
+            vault.last_withdrawal_window_start = 0;
+            vault.withdrawn_in_window = 0;
+            emit!(WithdrawRateLimitSetEvent {
+                vault: vault.key(),
+                owner: vault.owner,
+                window_seconds,
+                max_amount,
+            });
+        }
+        PolicyChange::AddWhitelist { address } => {
+            if !vault.withdraw_whitelist.contains(&address) {
+                vault.withdraw_whitelist.push(address);
+                emit!(WithdrawWhitelistUpdatedEvent {
+                    vault: vault.key(),
+                    owner: vault.owner,
+                    address,
+                    added: true,
+                    new_len: vault.withdraw_whitelist.len() as u32
+                });
+            }
+        }
+        PolicyChange::Multisig { signers, threshold } => {
+            vault.multisig_threshold = threshold;
+            vault.multisig_signers = signers;
+        }
+        PolicyChange::AddDelegate { delegate } => {
+            if !vault.delegates.contains(&delegate) {
+                require!(vault.delegates.len() < MAX_DELEGATES, ErrorCode::Overflow);
+                vault.delegates.push(delegate);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply every queued security-policy change whose delay has passed.
+pub fn apply_policy_changes(ctx: Context<UpdatePolicy>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault
+        .record_owner_activity(ctx.accounts.owner.key(), &[], now);
+    let vault = &mut ctx.accounts.vault;
+    let matured = vault.take_matured_policy_changes(now);
+    require!(!matured.is_empty(), ErrorCode::PolicyChangeNotReady);
+    for change in matured {
+        apply_policy_change(vault, change)?;
+    }
+    Ok(())
+}
+
+/// Drop the queued security-policy change at `index`.
+pub fn cancel_policy_change(ctx: Context<RestrictPolicy>, index: u8) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    let vault = &mut ctx.accounts.vault;
+    require!(
+        (index as usize) < vault.pending_policy_changes.len(),
+        ErrorCode::NotFound
+    );
+    let pending = vault.pending_policy_changes.remove(index as usize);
+    emit!(PolicyChangeCancelledEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        change: pending.change,
+    });
+    Ok(())
+}
@@ -0,0 +1,382 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::constants::{BPS_DENOMINATOR, VAULT_SEED, YIELD_POSITION_SEED, YIELD_STRATEGY_SEED};
+use crate::error::ErrorCode;
+use crate::events::{YieldLossEvent, YieldRebalanceEvent, YieldTargetWeightSetEvent};
+use crate::state::{CollateralVault, VaultAuthority, YieldPosition, YieldStrategy};
+use crate::yield_adapter::{invoke_adapter, AdapterAccounts, AdapterInstruction};
+
+/// Set the target share (bps of deployed yield) for one strategy. The sum of
+/// targets across a vault's positions may not exceed 100%.
+pub fn set_yield_target_weight(
+    ctx: Context<SetYieldTargetWeight>,
+    target_weight_bps: u16,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    ctx.accounts
+        .vault
+        .check_authority(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+
+    let yp = ctx.accounts.yield_program.key();
+    require!(
+        ctx.accounts.vault_authority.yield_whitelist.contains(&yp),
+        ErrorCode::YieldProgramNotWhitelisted
+    );
+
+    let vault_key = ctx.accounts.vault.key();
+    let position = &mut ctx.accounts.yield_position;
+    position.bind(vault_key, yp, None, ctx.bumps.yield_position)?;
+
+    let vault = &mut ctx.accounts.vault;
+    let total = (vault.yield_target_weight_bps as u64)
+        .checked_sub(position.target_weight_bps as u64)
+        .and_then(|v| v.checked_add(target_weight_bps as u64))
+        .ok_or(ErrorCode::Overflow)?;
+    require!(total <= BPS_DENOMINATOR, ErrorCode::InvalidYieldWeights);
+
+    position.target_weight_bps = target_weight_bps;
+    vault.yield_target_weight_bps = total as u16;
+
+    emit!(YieldTargetWeightSetEvent {
+        vault: vault_key,
+        program: yp,
+        target_weight_bps,
+        total_target_weight_bps: vault.yield_target_weight_bps,
+    });
+    Ok(())
+}
+
+/// Move up to `amount` of principal from one strategy to another. The move must
+/// not exceed the source's excess over its target nor the destination's
+/// shortfall, so every rebalance brings both positions closer to their weights.
+///
+/// Remaining accounts: the first `from_extra_accounts` non-signer accounts go to
+/// the source adapter, the rest to the destination adapter. Multisig approvals
+/// (signers) may appear anywhere and are never forwarded.
+pub fn rebalance_yield<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RebalanceYield<'info>>,
+    amount: u64,
+    from_extra_accounts: u8,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    ctx.accounts
+        .vault
+        .check_authority(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+
+    let from_yp = ctx.accounts.from_yield_program.key();
+    let to_yp = ctx.accounts.to_yield_program.key();
+    require_keys_neq!(from_yp, to_yp, ErrorCode::InvalidAmount);
+    let whitelist = &ctx.accounts.vault_authority.yield_whitelist;
+    require!(
+        whitelist.contains(&from_yp) && whitelist.contains(&to_yp),
+        ErrorCode::YieldProgramNotWhitelisted
+    );
+    // Impaired strategies take no new funds; moving out of one is allowed
+    require!(
+        !ctx.accounts.to_yield_strategy.impaired,
+        ErrorCode::YieldStrategyImpaired
+    );
+    let to_strategy_bump = ctx.bumps.to_yield_strategy;
+    let to_strategy = &mut ctx.accounts.to_yield_strategy;
+    if to_strategy.yield_program == Pubkey::default() {
+        to_strategy.yield_program = to_yp;
+        to_strategy.bump = to_strategy_bump;
+    }
+
+    let vault_key = ctx.accounts.vault.key();
+    let to_receipt_key = ctx.accounts.to_receipt_token_account.key();
+    let to_bump = ctx.accounts.to_position.bump;
+    ctx.accounts
+        .to_position
+        .bind(vault_key, to_yp, Some(to_receipt_key), to_bump)?;
+
+    // Only move toward the targets
+    let deployed = ctx.accounts.vault.yield_deposited_balance;
+    let from_position = &ctx.accounts.from_position;
+    let to_position = &ctx.accounts.to_position;
+    let excess = from_position
+        .deposited
+        .saturating_sub(from_position.target_amount(deployed));
+    let shortfall = to_position
+        .target_amount(deployed)
+        .saturating_sub(to_position.deposited);
+    require!(
+        amount <= excess && amount <= shortfall,
+        ErrorCode::RebalanceNotTowardTarget
+    );
+
+    let split = core::cmp::min(from_extra_accounts as usize, ctx.remaining_accounts.len());
+    let (from_extras, to_extras) = ctx.remaining_accounts.split_at(split);
+
+    let vault_seed = ctx.accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+
+    // Leg 1: withdraw from the source strategy
+    let vault_before = ctx.accounts.vault_token_account.amount;
+    let from_receipts_before = ctx.accounts.from_receipt_token_account.amount;
+    invoke_adapter(
+        &ctx.accounts.from_yield_program.to_account_info(),
+        AdapterInstruction::Withdraw { amount },
+        AdapterAccounts {
+            vault: ctx.accounts.vault.to_account_info(),
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            receipt_token_account: ctx.accounts.from_receipt_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        from_extras,
+        signer,
+    )?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.from_receipt_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .vault_token_account
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    require!(
+        received > 0
+            && received <= amount
+            && ctx.accounts.from_receipt_token_account.amount < from_receipts_before,
+        ErrorCode::YieldAdapterMismatch
+    );
+
+    // Leg 2: deposit what was received into the destination strategy
+    let vault_mid = ctx.accounts.vault_token_account.amount;
+    let to_receipts_before = ctx.accounts.to_receipt_token_account.amount;
+    invoke_adapter(
+        &ctx.accounts.to_yield_program.to_account_info(),
+        AdapterInstruction::Deposit { amount: received },
+        AdapterAccounts {
+            vault: ctx.accounts.vault.to_account_info(),
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            receipt_token_account: ctx.accounts.to_receipt_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        to_extras,
+        signer,
+    )?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.to_receipt_token_account.reload()?;
+    let spent = vault_mid
+        .checked_sub(ctx.accounts.vault_token_account.amount)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    require!(
+        spent > 0
+            && spent <= received
+            && ctx.accounts.to_receipt_token_account.amount > to_receipts_before,
+        ErrorCode::YieldAdapterMismatch
+    );
+
+    // Accounting: received leaves the source, spent enters the destination, any
+    // remainder stays liquid in the vault; principal behind burned receipts that
+    // did not come back is written down, unburned receipts stay deployed
+    let from_receipts_burned = from_receipts_before
+        .checked_sub(ctx.accounts.from_receipt_token_account.amount)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    let from_position = &mut ctx.accounts.from_position;
+    let redeemed = from_position.principal_for_receipts(from_receipts_burned, from_receipts_before);
+    from_position.deposited = from_position
+        .deposited
+        .checked_sub(received)
+        .ok_or(ErrorCode::Overflow)?;
+    let shortfall = redeemed.saturating_sub(received);
+    let (principal_loss, _) =
+        from_position.write_down(&mut ctx.accounts.vault, shortfall, false)?;
+
+    let to_position = &mut ctx.accounts.to_position;
+    to_position.deposited = to_position
+        .deposited
+        .checked_add(spent)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // Strategy caps: the move counts against the destination like a deposit
+    let outflow = received
+        .checked_add(principal_loss)
+        .ok_or(ErrorCode::Overflow)?;
+    YieldStrategy::record_flows(&ctx.accounts.from_yield_strategy, 0, outflow)?;
+    let to_strategy = &mut ctx.accounts.to_yield_strategy;
+    to_strategy.apply_flows(spent, 0)?;
+    to_strategy.check_capacity(
+        to_strategy.total_deposited,
+        ctx.accounts.vault_authority.total_value_locked,
+    )?;
+
+    let vault = &mut ctx.accounts.vault;
+    let leftover = received.checked_sub(spent).ok_or(ErrorCode::Overflow)?;
+    vault.yield_deposited_balance = vault
+        .yield_deposited_balance
+        .checked_sub(leftover)
+        .ok_or(ErrorCode::Overflow)?;
+    vault.available_balance = vault
+        .available_balance
+        .checked_add(leftover)
+        .ok_or(ErrorCode::Overflow)?;
+    vault.active_yield_program = to_yp;
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    if principal_loss > 0 {
+        emit!(YieldLossEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            program: from_yp,
+            principal_loss,
+            accrued_written_off: 0,
+            new_yield_balance: vault.yield_deposited_balance,
+            new_total_balance: vault.total_balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+
+    emit!(YieldRebalanceEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        from_program: from_yp,
+        to_program: to_yp,
+        withdrawn_amount: received,
+        deposited_amount: spent,
+        from_deposited: ctx.accounts.from_position.deposited,
+        to_deposited: ctx.accounts.to_position.deposited,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetYieldTargetWeight<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Owner used for PDA seeds
+    /// CHECK: seed/equality only
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+        seeds = [crate::constants::VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// CHECK: key checked against yield_whitelist
+    pub yield_program: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = YieldPosition::LEN,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RebalanceYield<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Owner used for PDA seeds
+    /// CHECK: seed/equality only
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+        seeds = [crate::constants::VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: key checked against yield_whitelist; must be executable
+    pub from_yield_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), from_yield_program.key().as_ref()],
+        bump = from_position.bump,
+        constraint = from_position.receipt_token_account == from_receipt_token_account.key() @ ErrorCode::YieldAdapterMismatch,
+    )]
+    pub from_position: Account<'info, YieldPosition>,
+
+    /// Strategy record for cap accounting (may be uninitialized)
+    /// CHECK: address fixed by seeds; updated via YieldStrategy::record_flows
+    #[account(mut, seeds = [YIELD_STRATEGY_SEED, from_yield_program.key().as_ref()], bump)]
+    pub from_yield_strategy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = from_receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = from_receipt_token_account.key() != vault_token_account.key() @ ErrorCode::Unauthorized,
+    )]
+    pub from_receipt_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: key checked against yield_whitelist; must be executable
+    pub to_yield_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), to_yield_program.key().as_ref()],
+        bump = to_position.bump,
+    )]
+    pub to_position: Account<'info, YieldPosition>,
+
+    /// Governance flags and caps for the destination strategy
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = YieldStrategy::LEN,
+        seeds = [YIELD_STRATEGY_SEED, to_yield_program.key().as_ref()],
+        bump
+    )]
+    pub to_yield_strategy: Account<'info, YieldStrategy>,
+
+    #[account(
+        mut,
+        constraint = to_receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = to_receipt_token_account.key() != vault_token_account.key() @ ErrorCode::Unauthorized,
+        constraint = to_receipt_token_account.key() != from_receipt_token_account.key() @ ErrorCode::Unauthorized,
+    )]
+    pub to_receipt_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
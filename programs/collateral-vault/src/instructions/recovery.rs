@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::VAULT_SEED;
+use crate::error::ErrorCode;
+use crate::events::{
+    GuardianChangeCancelledEvent, GuardiansProposedEvent, GuardiansSetEvent, OwnerRecoveredEvent,
+    RecoveryStartedEvent, RecoveryVetoedEvent,
+};
+use crate::instructions::withdraw_policy::{RestrictPolicy, UpdatePolicy};
+use crate::state::CollateralVault;
+
+/// Queue a new guardian set and threshold; it takes effect after
+/// `GUARDIAN_CHANGE_DELAY_SECONDS` via `apply_guardians`.
+pub fn propose_guardians(
+    ctx: Context<UpdatePolicy>,
+    guardians: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let now = Clock::get()?.unix_timestamp;
+    vault.record_owner_activity(ctx.accounts.owner.key(), &[], now);
+    let effective_at = vault.propose_guardians(guardians.clone(), threshold, now)?;
+    emit!(GuardiansProposedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        guardians,
+        threshold,
+        effective_at,
+    });
+    Ok(())
+}
+
+/// Install the proposed guardian set once its delay has passed.
+pub fn apply_guardians(ctx: Context<UpdatePolicy>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let now = Clock::get()?.unix_timestamp;
+    vault.record_owner_activity(ctx.accounts.owner.key(), &[], now);
+    vault.apply_pending_guardians(now)?;
+    emit!(GuardiansSetEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        guardians: vault.guardians.clone(),
+        threshold: vault.guardian_threshold,
+    });
+    Ok(())
+}
+
+/// Drop a proposed guardian set before it takes effect.
+pub fn cancel_guardian_change(ctx: Context<RestrictPolicy>) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    let vault = &mut ctx.accounts.vault;
+    require!(vault.pending_guardians.is_some(), ErrorCode::NotFound);
+    vault.pending_guardians = None;
+    emit!(GuardianChangeCancelledEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+    });
+    Ok(())
+}
+
+/// Guardians (threshold signers among `authority` and `remaining_accounts`)
+/// start rotating the vault to `new_owner`.
+pub fn start_recovery(ctx: Context<GuardianRecovery>, new_owner: Pubkey) -> Result<()> {
+    ctx.accounts
+        .vault
+        .check_guardians(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    let executable_at = vault.start_recovery(new_owner, now)?;
+    emit!(RecoveryStartedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        new_owner,
+        executable_at,
+    });
+    Ok(())
+}
+
+/// The current owner (or multisig threshold) cancels a pending recovery.
+pub fn veto_recovery(ctx: Context<GuardianRecovery>) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    ctx.accounts
+        .vault
+        .check_owner_authority(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+    let vault = &mut ctx.accounts.vault;
+    let recovery = vault.pending_recovery.take().ok_or(ErrorCode::NotFound)?;
+    emit!(RecoveryVetoedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        new_owner: recovery.new_owner,
+    });
+    Ok(())
+}
+
+/// Finish a recovery whose veto window has passed. Anyone may submit it.
+pub fn complete_recovery(ctx: Context<GuardianRecovery>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    let old_owner = vault.complete_recovery(now)?;
+    emit!(OwnerRecoveredEvent {
+        vault: vault.key(),
+        old_owner,
+        new_owner: vault.owner,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GuardianRecovery<'info> {
+    pub authority: Signer<'info>,
+
+    /// Current owner, to check against the vault; need not sign
+    /// CHECK: equality check only
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+}
@@ -5,7 +5,13 @@ use crate::error::ErrorCode;
 use crate::events::TimelocksReleasedEvent;
 use crate::state::CollateralVault;
 
+#[allow(clippy::manual_contains)]
 pub fn handler(ctx: Context<ReleaseTimelocks>) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
     // Authorization: owner or delegates or multisig threshold (same rules as withdraw)
     let authority = &ctx.accounts.authority;
     let vault_owner = ctx.accounts.vault.owner;
@@ -89,7 +95,7 @@ pub struct ReleaseTimelocks<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
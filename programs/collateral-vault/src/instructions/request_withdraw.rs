@@ -6,6 +6,11 @@ use crate::events::WithdrawRequestedEvent;
 use crate::state::CollateralVault;
 
 pub fn handler(ctx: Context<RequestWithdraw>, amount: u64) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
     require!(amount > 0, ErrorCode::InvalidAmount);
 
     let now = Clock::get()?.unix_timestamp;
@@ -43,9 +48,10 @@ pub struct RequestWithdraw<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
 }
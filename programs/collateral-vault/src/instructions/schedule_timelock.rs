@@ -3,10 +3,17 @@ use anchor_lang::prelude::*;
 use crate::constants::VAULT_SEED;
 use crate::error::ErrorCode;
 use crate::events::{TimelockScheduledEvent, TransactionEvent};
+use crate::instructions::co_signer::enforce_co_signer;
 use crate::state::CollateralVault;
 use crate::types::{TimelockEntry, TransactionType};
 
+#[allow(clippy::manual_contains)]
 pub fn handler(ctx: Context<ScheduleTimelock>, amount: u64, duration_seconds: i64) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
     require!(amount > 0, ErrorCode::InvalidAmount);
     require!(duration_seconds >= 0, ErrorCode::InvalidAmount);
 
@@ -59,6 +66,19 @@ pub fn handler(ctx: Context<ScheduleTimelock>, amount: u64, duration_seconds: i6
     // Business rule: must have available funds to reserve
     require!(available_balance >= amount, ErrorCode::InsufficientFunds);
 
+    // Second factor for amounts above the co-signer threshold; the funds stay in
+    // the vault, so an approval names the vault as recipient
+    let vault_key = ctx.accounts.vault.key();
+    enforce_co_signer(
+        &mut ctx.accounts.vault,
+        vault_key,
+        amount,
+        vault_key,
+        authority.key(),
+        ctx.remaining_accounts,
+        &ctx.accounts.instructions,
+    )?;
+
     // Compute unlock time and push entry
     let now = Clock::get()?.unix_timestamp;
     let unlock_time = now
@@ -106,9 +126,14 @@ pub struct ScheduleTimelock<'info> {
     // Verify the provided vault PDA belongs to this user
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
+
+    /// CHECK: Instructions sysvar, read for ed25519 co-signer approvals
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
 }
@@ -1,25 +1,29 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use crate::constants::{VAULT_AUTHORITY_SEED, VAULT_SEED};
+use crate::constants::{INTEGRATOR_SEED, TREASURY_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED};
 use crate::error::ErrorCode;
 use crate::events::{TransactionEvent, TransferEvent};
-use crate::state::{CollateralVault, VaultAuthority};
+use crate::instructions::lock_collateral::resolve_caller_program;
+use crate::instructions::treasury::collect_fee;
+use crate::state::integrator_registry::CAP_TRANSFER;
+use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
 use crate::types::TransactionType;
-use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
 
 pub fn handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
 
     let va = &ctx.accounts.vault_authority;
+    // Transfer fee is taken out of the amount; the receiving vault is credited the rest
+    let fee = va.transfer_fee.fee_for(amount);
+    let credited = amount - fee;
     // Optional global freeze
     require!(!va.freeze, ErrorCode::Frozen);
 
     let actual_caller = resolve_caller_program(&ctx.accounts.instructions)?;
-    require!(
-        va.authorized_programs.iter().any(|p| *p == actual_caller),
-        ErrorCode::UnauthorizedProgram
-    );
+    ctx.accounts
+        .integrator
+        .require_capability(actual_caller, CAP_TRANSFER)?;
     if va.cpi_enforced {
         require_keys_eq!(
             ctx.accounts.caller_program.key(),
@@ -73,16 +77,19 @@ pub fn handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
         ErrorCode::InvalidTokenProgramOwner
     );
 
+    // The source vault's owner must have approved the calling integrator
+    from_vault.require_integrator(actual_caller)?;
+
     // Balance check
     require!(
         from_vault.available_balance >= amount,
         ErrorCode::InsufficientFunds
     );
 
-    // Seeds for PDA signer: ["vault", from_vault.owner]
-    let from_owner = from_vault.owner;
+    // Seeds for PDA signer: ["vault", original_owner]
     let from_bump = from_vault.bump;
-    let signer_seeds: &[&[u8]] = &[VAULT_SEED, from_owner.as_ref(), &[from_bump]];
+    let vault_seed = from_vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[from_bump]];
     let signer: &[&[&[u8]]] = &[signer_seeds];
 
     // CPI: transfer from from_vault ATA to to_vault ATA, signed by from_vault PDA
@@ -96,7 +103,15 @@ pub fn handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
         cpi_accounts,
         signer,
     );
-    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    anchor_spl::token::transfer(cpi_ctx, credited)?;
+    collect_fee(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.from_vault_token_account.to_account_info(),
+        ctx.accounts.treasury_token_account.to_account_info(),
+        from_vault.to_account_info(),
+        signer,
+        fee,
+    )?;
 
     // Update balances with checked arithmetic
     from_vault.total_balance = from_vault
@@ -109,35 +124,26 @@ pub fn handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
         .ok_or(ErrorCode::Overflow)?;
     to_vault.total_balance = to_vault
         .total_balance
-        .checked_add(amount)
+        .checked_add(credited)
         .ok_or(ErrorCode::Overflow)?;
     to_vault.available_balance = to_vault
         .available_balance
-        .checked_add(amount)
+        .checked_add(credited)
         .ok_or(ErrorCode::Overflow)?;
 
-    // Invariant: total = locked + available
-    require!(
-        from_vault.total_balance
-            == from_vault
-                .locked_balance
-                .checked_add(from_vault.available_balance)
-                .ok_or(ErrorCode::Overflow)?,
-        ErrorCode::InvariantViolation
-    );
-    require!(
-        to_vault.total_balance
-            == to_vault
-                .locked_balance
-                .checked_add(to_vault.available_balance)
-                .ok_or(ErrorCode::Overflow)?,
-        ErrorCode::InvariantViolation
-    );
+    // Protocol TVL (net deposits): the fee leaves the vaults
+    let va = &mut ctx.accounts.vault_authority;
+    va.total_value_locked = va.total_value_locked.saturating_sub(fee);
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    from_vault.check_balance_invariant()?;
+    to_vault.check_balance_invariant()?;
 
     emit!(TransferEvent {
         from_vault: from_vault.key(),
         to_vault: to_vault.key(),
         amount,
+        fee,
         from_new_total_balance: from_vault.total_balance,
         to_new_total_balance: to_vault.total_balance,
     });
@@ -154,7 +160,7 @@ pub fn handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
         vault: to_vault.key(),
         owner: to_vault.owner,
         transaction_type: TransactionType::Transfer,
-        amount,
+        amount: credited,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
@@ -163,20 +169,31 @@ pub fn handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct TransferCollateral<'info> {
-    /// CHECK: program id of the calling program, used for allowlist verification
+    /// CHECK: program id of the calling program, used for registry lookup
     pub caller_program: UncheckedAccount<'info>,
 
     #[account(
+		mut,
 		seeds = [VAULT_AUTHORITY_SEED],
 		bump = vault_authority.bump,
 	)]
     pub vault_authority: Account<'info, VaultAuthority>,
 
+    /// Registry record of the calling program; checked against the actual caller
+    #[account(
+        seeds = [INTEGRATOR_SEED, caller_program.key().as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
     /// CHECK: Instructions sysvar account for CPI-origin verification when enforced
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions: AccountInfo<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !from_vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
+    )]
     pub from_vault: Account<'info, CollateralVault>,
 
     #[account(mut)]
@@ -188,15 +205,13 @@ pub struct TransferCollateral<'info> {
     #[account(mut)]
     pub to_vault_token_account: Account<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
-}
+    /// Protocol treasury for the vault's mint; receives the transfer fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, from_vault.usdt_mint.as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
 
-fn resolve_caller_program(instructions: &AccountInfo<'_>) -> Result<Pubkey> {
-    let current_index = sysvar_instructions::load_current_index_checked(instructions)?;
-    require!(current_index > 0, ErrorCode::UnauthorizedProgram);
-    let caller_ix = sysvar_instructions::load_instruction_at_checked(
-        (current_index - 1) as usize,
-        instructions,
-    )?;
-    Ok(caller_ix.program_id)
+    pub token_program: Program<'info, Token>,
 }
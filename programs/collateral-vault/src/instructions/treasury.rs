@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::constants::{TREASURY_SEED, VAULT_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::events::{TreasuryInitializedEvent, TreasurySweptEvent};
+use crate::state::VaultAuthority;
+
+/// Create the protocol treasury token account for `mint`. Fees are paid into
+/// it; it is owned by the vault authority PDA so only the program can move funds.
+pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+    emit!(TreasuryInitializedEvent {
+        mint: ctx.accounts.mint.key(),
+        treasury: ctx.accounts.treasury_token_account.key(),
+    });
+    Ok(())
+}
+
+/// Move `amount` of collected fees out of the treasury (governance only).
+pub fn sweep_treasury(ctx: Context<SweepTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.treasury_token_account.amount >= amount,
+        ErrorCode::InsufficientFunds
+    );
+
+    let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_SEED, &[ctx.accounts.vault_authority.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    let cpi_accounts = anchor_spl::token::Transfer {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        to: ctx.accounts.destination_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    anchor_spl::token::transfer(cpi_ctx, amount)?;
+
+    emit!(TreasurySweptEvent {
+        mint: ctx.accounts.mint.key(),
+        destination: ctx.accounts.destination_token_account.key(),
+        amount,
+    });
+    Ok(())
+}
+
+/// Pay `fee` from a vault token account into the treasury, signed by the vault PDA.
+pub(crate) fn collect_fee<'info>(
+    token_program: AccountInfo<'info>,
+    vault_token_account: AccountInfo<'info>,
+    treasury_token_account: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    fee: u64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+    let cpi_accounts = anchor_spl::token::Transfer {
+        from: vault_token_account,
+        to: treasury_token_account,
+        authority: vault,
+    };
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(token_program, cpi_accounts, signer),
+        fee,
+    )
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = governance,
+        seeds = [TREASURY_SEED, mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepTreasury<'info> {
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == mint.key() @ ErrorCode::Unauthorized,
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
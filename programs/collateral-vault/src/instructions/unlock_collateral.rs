@@ -1,11 +1,12 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::VAULT_AUTHORITY_SEED;
+use crate::constants::{INTEGRATOR_SEED, VAULT_AUTHORITY_SEED};
 use crate::error::ErrorCode;
 use crate::events::{TransactionEvent, UnlockEvent};
-use crate::state::{CollateralVault, VaultAuthority};
+use crate::instructions::lock_collateral::resolve_caller_program;
+use crate::state::integrator_registry::CAP_UNLOCK;
+use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
 use crate::types::TransactionType;
-use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
 
 pub fn handler(ctx: Context<UnlockCollateral>, amount: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
@@ -16,10 +17,9 @@ pub fn handler(ctx: Context<UnlockCollateral>, amount: u64) -> Result<()> {
 
     let actual_caller = resolve_caller_program(&ctx.accounts.instructions)?;
 
-    require!(
-        va.authorized_programs.iter().any(|p| *p == actual_caller),
-        ErrorCode::UnauthorizedProgram
-    );
+    ctx.accounts
+        .integrator
+        .require_capability(actual_caller, CAP_UNLOCK)?;
 
     if va.cpi_enforced {
         require_keys_eq!(
@@ -32,6 +32,8 @@ pub fn handler(ctx: Context<UnlockCollateral>, amount: u64) -> Result<()> {
     let vault = &mut ctx.accounts.vault;
     require!(vault.locked_balance >= amount, ErrorCode::InsufficientFunds);
 
+    vault.record_integrator_unlock(actual_caller, amount)?;
+    ctx.accounts.integrator.record_unlock(amount);
     vault.locked_balance = vault
         .locked_balance
         .checked_sub(amount)
@@ -41,15 +43,8 @@ pub fn handler(ctx: Context<UnlockCollateral>, amount: u64) -> Result<()> {
         .checked_add(amount)
         .ok_or(ErrorCode::Overflow)?;
 
-    // Invariant: total = locked + available
-    require!(
-        vault.total_balance
-            == vault
-                .locked_balance
-                .checked_add(vault.available_balance)
-                .ok_or(ErrorCode::Overflow)?,
-        ErrorCode::InvariantViolation
-    );
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
 
     emit!(UnlockEvent {
         vault: vault.key(),
@@ -72,7 +67,7 @@ pub fn handler(ctx: Context<UnlockCollateral>, amount: u64) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct UnlockCollateral<'info> {
-    /// CHECK: program id of the calling program, used for allowlist verification
+    /// CHECK: program id of the calling program, used for registry lookup
     pub caller_program: UncheckedAccount<'info>,
 
     #[account(
@@ -81,6 +76,14 @@ pub struct UnlockCollateral<'info> {
     )]
     pub vault_authority: Account<'info, VaultAuthority>,
 
+    /// Registry record of the calling program; checked against the actual caller
+    #[account(
+        mut,
+        seeds = [INTEGRATOR_SEED, caller_program.key().as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
     /// CHECK: Instructions sysvar account for CPI-origin verification when enforced
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions: AccountInfo<'info>,
@@ -88,13 +91,3 @@ pub struct UnlockCollateral<'info> {
     #[account(mut)]
     pub vault: Account<'info, CollateralVault>,
 }
-
-fn resolve_caller_program(instructions: &AccountInfo<'_>) -> Result<Pubkey> {
-    let current_index = sysvar_instructions::load_current_index_checked(instructions)?;
-    require!(current_index > 0, ErrorCode::UnauthorizedProgram);
-    let caller_ix = sysvar_instructions::load_instruction_at_checked(
-        (current_index - 1) as usize,
-        instructions,
-    )?;
-    Ok(caller_ix.program_id)
-}
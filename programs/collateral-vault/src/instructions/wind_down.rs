@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    INTEGRATOR_SEED, MAX_SETTLEMENT_ENTRIES, MIN_INTEGRATOR_GRACE_PERIOD_SECONDS,
+    VAULT_AUTHORITY_SEED, VAULT_SEED,
+};
+use crate::error::ErrorCode;
+use crate::events::{
+    IntegratorDeprecatedEvent, StrandedLockReleasedEvent, UnattributedLockReleasedEvent,
+};
+use crate::instructions::integrator_registry::UpdateIntegrator;
+use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
+
+/// Start winding an integrator down. From now on it may only unlock; once
+/// `grace_period_seconds` have passed, whatever it still holds locked can be
+/// released by vault owners or in bulk by governance. Deprecation is final.
+pub fn deprecate_integrator(
+    ctx: Context<UpdateIntegrator>,
+    grace_period_seconds: i64,
+) -> Result<()> {
+    require!(
+        grace_period_seconds >= MIN_INTEGRATOR_GRACE_PERIOD_SECONDS,
+        ErrorCode::InvalidAmount
+    );
+    let integrator = &mut ctx.accounts.integrator;
+    require!(!integrator.is_deprecated(), ErrorCode::IntegratorDeprecated);
+
+    let now = Clock::get()?.unix_timestamp;
+    integrator.deprecated_at = now;
+    integrator.grace_period_ends_at = now
+        .checked_add(grace_period_seconds)
+        .ok_or(ErrorCode::Overflow)?;
+    integrator.updated_at = now;
+
+    emit!(IntegratorDeprecatedEvent {
+        program: integrator.program,
+        deprecated_at: integrator.deprecated_at,
+        grace_period_ends_at: integrator.grace_period_ends_at,
+        total_locked: integrator.total_locked,
+    });
+    Ok(())
+}
+
+/// Owner self-service: release what a wound-down integrator still holds locked
+/// in this vault and drop its approval.
+pub fn reclaim_stranded_lock(ctx: Context<ReclaimStrandedLock>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault
+        .record_owner_activity(ctx.accounts.owner.key(), &[], now);
+    release(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.integrator,
+        now,
+        false,
+    )
+}
+
+/// Governance: release a wound-down integrator's locks across many vaults.
+///
+/// Remaining accounts: the vaults to settle, writable.
+pub fn settle_stranded_locks<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleStrandedLocks<'info>>,
+) -> Result<()> {
+    require!(
+        !ctx.remaining_accounts.is_empty()
+            && ctx.remaining_accounts.len() <= MAX_SETTLEMENT_ENTRIES,
+        ErrorCode::InvalidSettlement
+    );
+    let now = Clock::get()?.unix_timestamp;
+    for vault_ai in ctx.remaining_accounts.iter() {
+        require!(vault_ai.is_writable, ErrorCode::InvalidSettlement);
+        let mut vault: Account<'info, CollateralVault> = Account::try_from(vault_ai)?;
+        release(&mut vault, &mut ctx.accounts.integrator, now, true)?;
+        vault.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+/// Governance: release collateral locked by the version-1 deployment. Those
+/// locks predate per-integrator attribution, so no integrator can unlock them
+/// and no lease or wind-down covers them. Governance calls this once the
+/// positions they backed are closed.
+///
+/// Remaining accounts: the vaults to settle, writable.
+pub fn release_unattributed_locks<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReleaseUnattributedLocks<'info>>,
+) -> Result<()> {
+    require!(
+        !ctx.remaining_accounts.is_empty()
+            && ctx.remaining_accounts.len() <= MAX_SETTLEMENT_ENTRIES,
+        ErrorCode::InvalidSettlement
+    );
+    let now = Clock::get()?.unix_timestamp;
+    for vault_ai in ctx.remaining_accounts.iter() {
+        require!(vault_ai.is_writable, ErrorCode::InvalidSettlement);
+        let mut vault: Account<'info, CollateralVault> = Account::try_from(vault_ai)?;
+        let amount = vault.release_unattributed_lock()?;
+
+        // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+        vault.check_balance_invariant()?;
+        vault.exit(&crate::ID)?;
+
+        emit!(UnattributedLockReleasedEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            amount,
+            timestamp: now,
+        });
+    }
+    Ok(())
+}
+
+fn release(
+    vault: &mut Account<'_, CollateralVault>,
+    integrator: &mut Account<'_, IntegratorRegistry>,
+    now: i64,
+    by_governance: bool,
+) -> Result<()> {
+    require!(
+        integrator.is_wound_down(now),
+        ErrorCode::IntegratorGracePeriodActive
+    );
+    let amount = vault.release_stranded_lock(integrator.program)?;
+    integrator.record_unlock(amount);
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    emit!(StrandedLockReleasedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program: integrator.program,
+        amount,
+        by_governance,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReclaimStrandedLock<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+        mut,
+        seeds = [INTEGRATOR_SEED, integrator.program.as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct SettleStrandedLocks<'info> {
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    #[account(
+        mut,
+        seeds = [INTEGRATOR_SEED, integrator.program.as_ref()],
+        bump = integrator.bump,
+    )]
+    pub integrator: Account<'info, IntegratorRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseUnattributedLocks<'info> {
+    pub governance: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+        has_one = governance @ ErrorCode::Unauthorized,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+}
@@ -1,12 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use crate::constants::{VAULT_AUTHORITY_SEED, VAULT_SEED};
+use crate::attestation::find_risk_attestation;
+use crate::constants::{
+    MAX_ATTESTATION_TTL_SECONDS, TREASURY_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED,
+};
 use crate::error::ErrorCode;
-use crate::events::{TransactionEvent, WithdrawEvent};
-use crate::state::{CollateralVault, VaultAuthority};
+use crate::events::{
+    InheritanceWithdrawalEvent, RiskAttestationAcceptedEvent, TransactionEvent, WithdrawEvent,
+};
+use crate::instructions::accrue_fees::charge_custody_fee;
+use crate::instructions::co_signer::enforce_co_signer;
+use crate::instructions::treasury::collect_fee;
+use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
 use crate::types::{PositionSummary, TransactionType};
 
+#[allow(clippy::manual_contains)]
 pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
 
@@ -20,7 +29,14 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     let vault_key = ctx.accounts.vault.key();
     let usdt_mint = ctx.accounts.vault.usdt_mint;
 
-    let authorized_programs = ctx.accounts.vault_authority.authorized_programs.clone();
+    // Every integrator the owner has approved must vouch for the vault via a position summary
+    let authorized_programs: Vec<Pubkey> = ctx
+        .accounts
+        .vault
+        .integrators
+        .iter()
+        .map(|i| i.program)
+        .collect();
     require!(
         ctx.remaining_accounts.len() >= authorized_programs.len(),
         ErrorCode::PositionSummaryMissing
@@ -28,16 +44,26 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     let (summary_accounts, signer_accounts) =
         ctx.remaining_accounts.split_at(authorized_programs.len());
 
-    // Authorization: single-owner or multisig
+    // An owner signature restarts the inheritance clock before it is consulted
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault
+        .record_owner_activity(authority.key(), signer_accounts, now);
+    // A beneficiary may withdraw to itself once the owner has gone inactive
+    let inheriting = ctx.accounts.vault.can_inherit(&authority.key(), now);
+
+    // Authorization: single-owner, multisig or inheriting beneficiary
     let threshold = ctx.accounts.vault.multisig_threshold;
     if threshold == 0 {
         // single-owner mode: allow owner or any configured delegate
         let auth = authority.key();
         require!(
-            auth == vault_owner || ctx.accounts.vault.delegates.iter().any(|d| *d == auth),
+            auth == vault_owner
+                || ctx.accounts.vault.delegates.iter().any(|d| *d == auth)
+                || inheriting,
             ErrorCode::Unauthorized
         );
-    } else {
+    } else if !inheriting {
         // multisig: require at least threshold unique configured signers to have signed
         let allowed: &Vec<Pubkey> = &ctx.accounts.vault.multisig_signers;
         require!(!allowed.is_empty(), ErrorCode::Unauthorized);
@@ -98,36 +124,120 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         vault_ref.timelocks = remaining;
     }
 
-    // Refresh available balance snapshot after potential timelock releases
+    // Custody fees are settled before the withdrawal is sized
+    charge_custody_fee(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.vault_authority,
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.treasury_token_account,
+        &ctx.accounts.token_program,
+    )?;
+
+    // Refresh available balance snapshot after potential timelock releases and fees
     let available_balance = ctx.accounts.vault.available_balance;
 
-    // Validate position summaries supplied by authorized programs
+    // Free-margin mode: open positions are fine as long as what stays in the
+    // vault covers the margin every integrator reports as required
+    let free_margin = ctx.accounts.vault_authority.free_margin_withdrawals
+        && !ctx.accounts.vault.strict_withdrawals;
+    let clock = Clock::get()?;
+    let mut required_margin: u64 = 0;
+    // Attestation nonces to record once every integrator has been checked
+    let mut attested_nonces: Vec<(Pubkey, u64)> = Vec::new();
+
+    // Validate position summaries supplied by approved integrators
     if !authorized_programs.is_empty() {
         let mut covered: std::collections::BTreeSet<Pubkey> = std::collections::BTreeSet::new();
         for summary_ai in summary_accounts.iter() {
-            let owner_program = *summary_ai.owner;
-            require!(
-                authorized_programs.iter().any(|p| *p == owner_program),
-                ErrorCode::PositionSummaryInvalid
-            );
-            require!(
-                !summary_ai.data_is_empty(),
-                ErrorCode::PositionSummaryInvalid
-            );
-            let data = summary_ai.try_borrow_data()?;
-            require!(data.len() >= 8, ErrorCode::PositionSummaryInvalid);
-            let summary = PositionSummary::try_from_slice(&data[8..])
-                .map_err(|_| ErrorCode::PositionSummaryInvalid)?;
-            drop(data);
-            require_keys_eq!(summary.vault, vault_key, ErrorCode::PositionSummaryInvalid);
-            require_keys_eq!(
-                summary.owner,
-                vault_owner,
-                ErrorCode::PositionSummaryInvalid
-            );
-            require!(summary.open_positions == 0, ErrorCode::OpenPositionsExist);
-            require!(summary.locked_amount == 0, ErrorCode::OpenPositionsExist);
-            covered.insert(owner_program);
+            // (program, open positions, locked amount, required margin, fresh)
+            let report = if *summary_ai.owner == crate::ID {
+                // Off-chain integrator: its registry record stands in for the summary
+                // and a signed attestation in this transaction supplies the figures
+                let data = summary_ai.try_borrow_data()?;
+                let registry = IntegratorRegistry::try_deserialize(&mut &data[..])
+                    .map_err(|_| ErrorCode::PositionSummaryInvalid)?;
+                drop(data);
+                require!(
+                    authorized_programs.contains(&registry.program),
+                    ErrorCode::PositionSummaryInvalid
+                );
+                let signer = registry.risk_signer.ok_or(ErrorCode::AttestationMissing)?;
+                let attestation = find_risk_attestation(
+                    &ctx.accounts.instructions,
+                    &signer,
+                    &registry.program,
+                    &vault_key,
+                )?;
+                require_keys_eq!(
+                    attestation.owner,
+                    vault_owner,
+                    ErrorCode::AttestationInvalid
+                );
+                require!(
+                    clock.unix_timestamp <= attestation.expires_at
+                        && attestation.expires_at
+                            <= clock
+                                .unix_timestamp
+                                .saturating_add(MAX_ATTESTATION_TTL_SECONDS),
+                    ErrorCode::AttestationExpired
+                );
+                attested_nonces.push((registry.program, attestation.nonce));
+                emit!(RiskAttestationAcceptedEvent {
+                    vault: vault_key,
+                    owner: vault_owner,
+                    program: registry.program,
+                    open_positions: attestation.open_positions,
+                    required_margin: attestation.required_margin,
+                    nonce: attestation.nonce,
+                    expires_at: attestation.expires_at,
+                });
+                (
+                    registry.program,
+                    attestation.open_positions,
+                    0,
+                    attestation.required_margin,
+                    true,
+                )
+            } else {
+                let owner_program = *summary_ai.owner;
+                require!(
+                    authorized_programs.iter().any(|p| *p == owner_program),
+                    ErrorCode::PositionSummaryInvalid
+                );
+                // Discriminator, version and canonical PDA per the position interface
+                let data = summary_ai.try_borrow_data()?;
+                let summary = PositionSummary::decode(&data)
+                    .map_err(|_| ErrorCode::PositionSummaryInvalid)?;
+                drop(data);
+                summary
+                    .verify_address(&owner_program, summary_ai.key)
+                    .map_err(|_| ErrorCode::PositionSummaryInvalid)?;
+                require_keys_eq!(summary.vault, vault_key, ErrorCode::PositionSummaryInvalid);
+                require_keys_eq!(
+                    summary.owner,
+                    vault_owner,
+                    ErrorCode::PositionSummaryInvalid
+                );
+                (
+                    owner_program,
+                    summary.open_positions,
+                    summary.locked_amount,
+                    summary.required_margin,
+                    summary.is_fresh(clock.slot),
+                )
+            };
+            let (program, open_positions, locked_amount, margin, fresh) = report;
+            // Positions can open after a summary is written; only trust recent figures
+            require!(fresh, ErrorCode::PositionSummaryStale);
+            if free_margin {
+                required_margin = required_margin
+                    .checked_add(margin)
+                    .ok_or(ErrorCode::Overflow)?;
+            } else {
+                require!(open_positions == 0, ErrorCode::OpenPositionsExist);
+                require!(locked_amount == 0, ErrorCode::OpenPositionsExist);
+            }
+            covered.insert(program);
         }
         require!(
             covered.len() == authorized_programs.len(),
@@ -135,26 +245,70 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         );
     }
 
+    // Replay guard: each attestation must carry a nonce above the last one accepted
+    for (program, nonce) in attested_nonces {
+        let entry = ctx
+            .accounts
+            .vault
+            .integrators
+            .iter_mut()
+            .find(|i| i.program == program)
+            .ok_or(ErrorCode::IntegratorNotApproved)?;
+        require!(
+            nonce > entry.attestation_nonce,
+            ErrorCode::AttestationReplayed
+        );
+        entry.attestation_nonce = nonce;
+    }
+
     // Business invariants
     require!(available_balance >= amount, ErrorCode::InsufficientFunds);
-    // Enforce no-open-positions rule (no locked funds)
-    require!(
-        ctx.accounts.vault.locked_balance == 0,
-        ErrorCode::OpenPositionsExist
-    );
-    // Recipient must be owner or on whitelist
-    {
-        let recipient = user_token_account.owner;
-        let is_owner = recipient == ctx.accounts.owner.key();
-        let is_whitelisted = ctx
+    if free_margin {
+        // Locked plus available collateral left after the withdrawal must cover the margin
+        let remaining = ctx
             .accounts
             .vault
-            .withdraw_whitelist
-            .iter()
-            .any(|pk| *pk == recipient);
-        require!(is_owner || is_whitelisted, ErrorCode::Unauthorized);
+            .locked_balance
+            .checked_add(available_balance - amount)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            remaining >= required_margin,
+            ErrorCode::InsufficientFreeMargin
+        );
+    } else {
+        // Enforce no-open-positions rule (no locked funds)
+        require!(
+            ctx.accounts.vault.locked_balance == 0,
+            ErrorCode::OpenPositionsExist
+        );
+    }
+    // Recipient must be owner or on whitelist; a beneficiary only withdraws to itself
+    {
+        let recipient = user_token_account.owner;
+        if inheriting {
+            require_keys_eq!(recipient, authority.key(), ErrorCode::Unauthorized);
+        } else {
+            let is_owner = recipient == ctx.accounts.owner.key();
+            let is_whitelisted = ctx
+                .accounts
+                .vault
+                .withdraw_whitelist
+                .iter()
+                .any(|pk| *pk == recipient);
+            require!(is_owner || is_whitelisted, ErrorCode::Unauthorized);
+        }
     }
     require_keys_eq!(user_token_account.mint, usdt_mint, ErrorCode::Unauthorized);
+    // Second factor for amounts above the co-signer threshold
+    enforce_co_signer(
+        &mut ctx.accounts.vault,
+        vault_key,
+        amount,
+        user_token_account.key(),
+        authority.key(),
+        signer_accounts,
+        &ctx.accounts.instructions,
+    )?;
     require_keys_eq!(vault_token_account.mint, usdt_mint, ErrorCode::Unauthorized);
     require_keys_eq!(
         vault_token_account.owner,
@@ -249,14 +403,18 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         ErrorCode::InvalidTokenProgramOwner
     );
 
-    // Seeds for PDA signer: ["vault", vault.owner]
+    // Seeds for PDA signer: ["vault", original_owner]
+    let vault_seed = ctx.accounts.vault.original_owner;
     let signer_seeds: &[&[u8]] = &[
         crate::constants::VAULT_SEED,
-        vault_owner.as_ref(),
+        vault_seed.as_ref(),
         &[vault_bump],
     ];
     let signer: &[&[&[u8]]] = &[signer_seeds];
 
+    // Withdrawal fee is taken out of the amount; the vault is debited the full amount
+    let fee = ctx.accounts.vault_authority.withdraw_fee.fee_for(amount);
+
     // CPI: transfer from vault ATA to user's ATA, signed by vault PDA
     let cpi_accounts = anchor_spl::token::Transfer {
         from: ctx.accounts.vault_token_account.to_account_info(),
@@ -268,7 +426,15 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         cpi_accounts,
         signer,
     );
-    anchor_spl::token::transfer(cpi_ctx, amount)?;
+    anchor_spl::token::transfer(cpi_ctx, amount - fee)?;
+    collect_fee(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.vault_token_account.to_account_info(),
+        ctx.accounts.treasury_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        signer,
+        fee,
+    )?;
 
     // Update balances with checked arithmetic
     let vault = &mut ctx.accounts.vault;
@@ -285,10 +451,16 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         .checked_add(amount)
         .ok_or(ErrorCode::Overflow)?;
 
+    // Protocol TVL (net deposits)
+    let va = &mut ctx.accounts.vault_authority;
+    va.total_value_locked = va.total_value_locked.saturating_sub(amount);
+
+    let vault = &ctx.accounts.vault;
     emit!(WithdrawEvent {
         vault: vault.key(),
         owner: vault.owner,
         amount,
+        fee,
         new_total_balance: vault.total_balance,
         new_available_balance: vault.available_balance,
     });
@@ -301,6 +473,15 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         timestamp: Clock::get()?.unix_timestamp,
     });
 
+    if inheriting {
+        emit!(InheritanceWithdrawalEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            beneficiary: authority.key(),
+            amount,
+        });
+    }
+
     Ok(())
 }
 
@@ -316,13 +497,15 @@ pub struct Withdraw<'info> {
     // Verify the provided vault PDA belongs to this user
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
 
     #[account(
+        mut,
         seeds = [VAULT_AUTHORITY_SEED],
         bump = vault_authority.bump,
     )]
@@ -334,5 +517,17 @@ pub struct Withdraw<'info> {
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
 
+    /// Protocol treasury for the vault's mint; receives the withdrawal fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, vault.usdt_mint.as_ref()],
+        bump,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
     pub token_program: Program<'info, Token>,
+
+    /// CHECK: Instructions sysvar, read for ed25519 risk attestations
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
 }
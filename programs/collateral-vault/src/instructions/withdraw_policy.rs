@@ -1,63 +1,138 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::VAULT_SEED;
+use crate::constants::{BPS_DENOMINATOR, VAULT_AUTHORITY_SEED, VAULT_SEED};
 use crate::error::ErrorCode;
 use crate::events::{
-    WithdrawMinDelaySetEvent, WithdrawRateLimitSetEvent, WithdrawWhitelistUpdatedEvent,
+    AutoCompoundOptOutSetEvent, LiquidReserveSetEvent, StrictWithdrawalsSetEvent,
+    WithdrawWhitelistUpdatedEvent,
 };
-use crate::state::CollateralVault;
+use crate::instructions::policy_changes::submit_policy_change;
+use crate::state::{CollateralVault, VaultAuthority};
+use crate::types::PolicyChange;
 
-pub fn set_min_delay(ctx: Context<UpdatePolicy>, seconds: i64) -> Result<()> {
+/// Shortening the delay waits out the policy change delay; lengthening applies at once.
+pub fn set_min_delay(ctx: Context<UpdateSecurityPolicy>, seconds: i64) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
     require!(seconds >= 0, ErrorCode::InvalidAmount);
-    let vault = &mut ctx.accounts.vault;
-    vault.min_withdraw_delay_seconds = seconds;
-    emit!(WithdrawMinDelaySetEvent {
-        vault: vault.key(),
-        owner: vault.owner,
-        seconds
-    });
-    Ok(())
+    submit_policy_change(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_authority,
+        PolicyChange::MinWithdrawDelay { seconds },
+    )
 }
 
+/// Raising or disabling the limit waits out the policy change delay.
 pub fn set_rate_limit(
-    ctx: Context<UpdatePolicy>,
+    ctx: Context<UpdateSecurityPolicy>,
     window_seconds: u32,
     max_amount: u64,
 ) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    submit_policy_change(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_authority,
+        PolicyChange::RateLimit {
+            window_seconds,
+            max_amount,
+        },
+    )
+}
+
+/// Minimum share of `total_balance` (bps) that `yield_deposit` must leave available.
+pub fn set_liquid_reserve(ctx: Context<UpdatePolicy>, min_liquid_reserve_bps: u16) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    require!(
+        (min_liquid_reserve_bps as u64) <= BPS_DENOMINATOR,
+        ErrorCode::InvalidRiskParams
+    );
     let vault = &mut ctx.accounts.vault;
-    vault.rate_window_seconds = window_seconds;
-    vault.rate_limit_amount = max_amount;
-    // Reset window counters on policy change
-    vault.last_withdrawal_window_start = 0;
-    vault.withdrawn_in_window = 0;
-    emit!(WithdrawRateLimitSetEvent {
+    vault.min_liquid_reserve_bps = min_liquid_reserve_bps;
+    emit!(LiquidReserveSetEvent {
         vault: vault.key(),
         owner: vault.owner,
-        window_seconds,
-        max_amount,
+        min_liquid_reserve_bps,
     });
     Ok(())
 }
 
-pub fn add_whitelist(ctx: Context<UpdatePolicy>, address: Pubkey) -> Result<()> {
+/// Opt the vault out of (or back into) the permissionless compound crank.
+pub fn set_auto_compound_opt_out(ctx: Context<UpdatePolicy>, opt_out: bool) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
     let vault = &mut ctx.accounts.vault;
-    require!(
-        !vault.withdraw_whitelist.iter().any(|a| *a == address),
-        ErrorCode::AlreadyExists
+    vault.auto_compound_opt_out = opt_out;
+    emit!(AutoCompoundOptOutSetEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        opt_out,
+    });
+    Ok(())
+}
+
+/// Keep the strict withdraw rule (no open positions, nothing locked) for this
+/// vault even when governance enables free-margin withdrawals.
+pub fn set_strict_withdrawals(ctx: Context<UpdatePolicy>, strict: bool) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
     );
-    vault.withdraw_whitelist.push(address);
-    emit!(WithdrawWhitelistUpdatedEvent {
+    let vault = &mut ctx.accounts.vault;
+    vault.strict_withdrawals = strict;
+    emit!(StrictWithdrawalsSetEvent {
         vault: vault.key(),
         owner: vault.owner,
-        address,
-        added: true,
-        new_len: vault.withdraw_whitelist.len() as u32
+        strict,
     });
     Ok(())
 }
 
-pub fn remove_whitelist(ctx: Context<UpdatePolicy>, address: Pubkey) -> Result<()> {
+/// A new recipient only becomes usable after the policy change delay.
+pub fn add_whitelist(ctx: Context<UpdateSecurityPolicy>, address: Pubkey) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
+    require!(
+        !ctx.accounts.vault.withdraw_whitelist.contains(&address),
+        ErrorCode::AlreadyExists
+    );
+    submit_policy_change(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_authority,
+        PolicyChange::AddWhitelist { address },
+    )
+}
+
+pub fn remove_whitelist(ctx: Context<RestrictPolicy>, address: Pubkey) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.owner.key(),
+        &[],
+        Clock::get()?.unix_timestamp,
+    );
     let vault = &mut ctx.accounts.vault;
+    // Removing an address also drops a queued addition of it
+    let queued = vault.pending_policy_changes.len();
+    vault
+        .pending_policy_changes
+        .retain(|p| p.change != PolicyChange::AddWhitelist { address });
+    let dropped_queued = vault.pending_policy_changes.len() < queued;
     if let Some(i) = vault.withdraw_whitelist.iter().position(|a| *a == address) {
         vault.withdraw_whitelist.swap_remove(i);
         emit!(WithdrawWhitelistUpdatedEvent {
@@ -68,6 +143,8 @@ pub fn remove_whitelist(ctx: Context<UpdatePolicy>, address: Pubkey) -> Result<(
             new_len: vault.withdraw_whitelist.len() as u32
         });
         Ok(())
+    } else if dropped_queued {
+        Ok(())
     } else {
         err!(ErrorCode::NotFound)
     }
@@ -80,7 +157,42 @@ pub struct UpdatePolicy<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+}
+
+/// Owner changes to settings whose relaxation is delayed by the governance floor.
+#[derive(Accounts)]
+pub struct UpdateSecurityPolicy<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = vault_authority.bump)]
+    pub vault_authority: Account<'info, VaultAuthority>,
+}
+
+/// Owner changes that only narrow the vault's exposure; allowed while panic-locked.
+#[derive(Accounts)]
+pub struct RestrictPolicy<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
@@ -1,14 +1,23 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
 
-use crate::constants::VAULT_SEED;
+use crate::constants::{VAULT_SEED, YIELD_POSITION_SEED, YIELD_STRATEGY_SEED};
 use crate::error::ErrorCode;
 use crate::events::{TransactionEvent, YieldDepositEvent};
-use crate::state::{CollateralVault, VaultAuthority};
+use crate::state::{CollateralVault, VaultAuthority, YieldPosition, YieldStrategy};
 use crate::types::TransactionType;
-use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
-use anchor_lang::solana_program::program::invoke_signed;
+use crate::yield_adapter::{invoke_adapter, AdapterAccounts, AdapterInstruction};
 
-pub fn handler(ctx: Context<YieldDeposit>, amount: u64) -> Result<()> {
+#[allow(clippy::manual_contains)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, YieldDeposit<'info>>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
     require!(amount > 0, ErrorCode::InvalidAmount);
 
     // Authorization: single-owner or multisig
@@ -63,32 +72,125 @@ pub fn handler(ctx: Context<YieldDeposit>, amount: u64) -> Result<()> {
             .any(|p| *p == yp),
         ErrorCode::YieldProgramNotWhitelisted
     );
+    require!(
+        !ctx.accounts.yield_strategy.impaired,
+        ErrorCode::YieldStrategyImpaired
+    );
+    let strategy_bump = ctx.bumps.yield_strategy;
+    let strategy = &mut ctx.accounts.yield_strategy;
+    if strategy.yield_program == Pubkey::default() {
+        strategy.yield_program = yp;
+        strategy.bump = strategy_bump;
+    }
+
+    let risk_params = ctx
+        .accounts
+        .vault_authority
+        .risk_params_for(ctx.accounts.vault.risk_tier_override);
+
+    // Per-strategy position: created on first deposit, receipt account pinned
+    let vault_key = ctx.accounts.vault.key();
+    let receipt_key = ctx.accounts.receipt_token_account.key();
+    let position_bump = ctx.bumps.yield_position;
+    ctx.accounts
+        .yield_position
+        .bind(vault_key, yp, Some(receipt_key), position_bump)?;
 
     // Business invariants
-    let vault = &mut ctx.accounts.vault;
     require!(
-        vault.available_balance >= amount,
+        ctx.accounts.vault.available_balance >= amount,
         ErrorCode::InsufficientFunds
     );
 
-    // Accounting: move funds from available to yield_deposited
+    // Route the deposit through the adapter and measure what actually moved
+    let vault_before = ctx.accounts.vault_token_account.amount;
+    let receipts_before = ctx.accounts.receipt_token_account.amount;
+
+    let vault_seed = ctx.accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    invoke_adapter(
+        &ctx.accounts.yield_program.to_account_info(),
+        AdapterInstruction::Deposit { amount },
+        AdapterAccounts {
+            vault: ctx.accounts.vault.to_account_info(),
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            receipt_token_account: ctx.accounts.receipt_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        ctx.remaining_accounts,
+        signer,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.receipt_token_account.reload()?;
+    let spent = vault_before
+        .checked_sub(ctx.accounts.vault_token_account.amount)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    let receipts_minted = ctx
+        .accounts
+        .receipt_token_account
+        .amount
+        .checked_sub(receipts_before)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    // The adapter may take less than requested, never more, and must issue receipts
+    require!(
+        spent > 0 && spent <= amount && receipts_minted > 0,
+        ErrorCode::YieldAdapterMismatch
+    );
+
+    // Accounting: move the measured amount from available to yield_deposited
+    let position = &mut ctx.accounts.yield_position;
+    position.deposited = position
+        .deposited
+        .checked_add(spent)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let vault = &mut ctx.accounts.vault;
     vault.available_balance = vault
         .available_balance
-        .checked_sub(amount)
+        .checked_sub(spent)
         .ok_or(ErrorCode::Overflow)?;
     vault.yield_deposited_balance = vault
         .yield_deposited_balance
-        .checked_add(amount)
+        .checked_add(spent)
         .ok_or(ErrorCode::Overflow)?;
-    if vault.active_yield_program == Pubkey::default() {
-        vault.active_yield_program = yp;
+
+    // Risk limits: yield allocation share and free buffer after this deposit
+    if let Some(params) = risk_params {
+        params.check_yield(
+            vault.total_balance,
+            vault.yield_deposited_balance,
+            vault.available_balance,
+        )?;
     }
 
+    // Liquidity: keep the vault's minimum reserve available for margin
+    require!(
+        vault.available_balance >= vault.liquid_reserve_target(),
+        ErrorCode::LiquidReserveBreached
+    );
+
+    // Strategy caps: absolute and as a share of protocol TVL
+    let strategy = &mut ctx.accounts.yield_strategy;
+    strategy.apply_flows(spent, 0)?;
+    strategy.check_capacity(
+        strategy.total_deposited,
+        ctx.accounts.vault_authority.total_value_locked,
+    )?;
+
+    // Most recently used strategy
+    vault.active_yield_program = yp;
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
     emit!(YieldDepositEvent {
         vault: vault.key(),
         owner: vault.owner,
         program: yp,
-        amount,
+        amount: spent,
+        receipt_amount: receipts_minted,
         new_yield_balance: vault.yield_deposited_balance,
         new_available_balance: vault.available_balance,
     });
@@ -97,42 +199,10 @@ pub fn handler(ctx: Context<YieldDeposit>, amount: u64) -> Result<()> {
         vault: vault.key(),
         owner: vault.owner,
         transaction_type: TransactionType::YieldDeposit,
-        amount,
+        amount: spent,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
-    // Optional generic CPI passthrough: if remaining accounts are provided and caller
-    // constructed the external instruction off-chain, the runtime can attempt to
-    // execute it with the vault PDA as signer. This allows protocol-specific routing
-    // without baking program specifics here.
-    let signer_seeds: &[&[u8]] = &[
-        crate::constants::VAULT_SEED,
-        ctx.accounts.vault.owner.as_ref(),
-        &[ctx.accounts.vault.bump],
-    ];
-    let signer: &[&[&[u8]]] = &[signer_seeds];
-    let remaining = ctx.remaining_accounts;
-    if !remaining.is_empty() {
-        // Build metas by mirroring remaining account properties
-        let metas: Vec<AccountMeta> = remaining
-            .iter()
-            .map(|ai| AccountMeta {
-                pubkey: ai.key(),
-                is_signer: ai.is_signer || ai.key() == ctx.accounts.vault.key(),
-                is_writable: ai.is_writable,
-            })
-            .collect();
-        // Expect first remaining account to be the external program id info
-        let program_id = ctx.accounts.yield_program.key();
-        // No opaque data here; when wiring real integrations, pass proper data via a companion ix
-        let ix = Instruction {
-            program_id,
-            accounts: metas,
-            data: vec![],
-        };
-        let _ = invoke_signed(&ix, remaining, signer);
-    }
-
     Ok(())
 }
 
@@ -147,9 +217,10 @@ pub struct YieldDeposit<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !vault.is_panic_locked() @ ErrorCode::VaultPanicLocked,
     )]
     pub vault: Account<'info, CollateralVault>,
 
@@ -161,6 +232,43 @@ pub struct YieldDeposit<'info> {
     pub vault_authority: Account<'info, VaultAuthority>,
 
     /// The target yield program to route to (whitelisted)
-    /// CHECK: used for key only
+    /// CHECK: key checked against yield_whitelist; must be executable
     pub yield_program: UncheckedAccount<'info>,
+
+    /// Governance flags and caps for the target strategy
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = YieldStrategy::LEN,
+        seeds = [YIELD_STRATEGY_SEED, yield_program.key().as_ref()],
+        bump
+    )]
+    pub yield_strategy: Account<'info, YieldStrategy>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Vault-owned token account holding the strategy's receipt tokens
+    #[account(
+        mut,
+        constraint = receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = receipt_token_account.key() != vault_token_account.key() @ ErrorCode::Unauthorized,
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = YieldPosition::LEN,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
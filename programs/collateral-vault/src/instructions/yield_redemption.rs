@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::constants::{
+    VAULT_SEED, YIELD_POSITION_SEED, YIELD_REDEMPTION_SEED, YIELD_REDEMPTION_TIMEOUT_SECONDS,
+    YIELD_STRATEGY_SEED,
+};
+use crate::error::ErrorCode;
+use crate::events::{YieldLossEvent, YieldRedemptionClaimedEvent, YieldRedemptionRequestedEvent};
+use crate::state::{
+    CollateralVault, VaultAuthority, YieldPosition, YieldRedemption, YieldStrategy,
+};
+use crate::yield_adapter::{invoke_adapter, AdapterAccounts, AdapterInstruction};
+
+/// Open a redemption ticket for `amount` of principal in a strategy that cannot
+/// redeem immediately. The principal leaves `yield_deposited_balance`; whatever
+/// the adapter pays right away becomes available and the rest is tracked in
+/// `pending_redemption_balance` until claimed.
+pub fn request_yield_withdraw<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RequestYieldWithdraw<'info>>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    ctx.accounts
+        .vault
+        .check_authority(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+
+    let yp = ctx.accounts.yield_program.key();
+    require!(
+        ctx.accounts.vault_authority.yield_whitelist.contains(&yp),
+        ErrorCode::YieldProgramNotWhitelisted
+    );
+    // One open ticket per vault and strategy
+    require!(
+        ctx.accounts.redemption.remaining() == 0,
+        ErrorCode::RedemptionTicketOpen
+    );
+    require!(
+        ctx.accounts.yield_position.deposited >= amount,
+        ErrorCode::InsufficientYieldBalance
+    );
+
+    let vault_before = ctx.accounts.vault_token_account.amount;
+    let receipts_before = ctx.accounts.receipt_token_account.amount;
+
+    let vault_seed = ctx.accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    invoke_adapter(
+        &ctx.accounts.yield_program.to_account_info(),
+        AdapterInstruction::RequestWithdraw { amount },
+        AdapterAccounts {
+            vault: ctx.accounts.vault.to_account_info(),
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            receipt_token_account: ctx.accounts.receipt_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        ctx.remaining_accounts,
+        signer,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.receipt_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .vault_token_account
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    // The adapter must take receipts and may pay out at most the requested amount
+    require!(
+        received <= amount && ctx.accounts.receipt_token_account.amount < receipts_before,
+        ErrorCode::YieldAdapterMismatch
+    );
+    let pending = amount - received;
+
+    let position = &mut ctx.accounts.yield_position;
+    position.deposited = position
+        .deposited
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    YieldStrategy::record_flows(&ctx.accounts.yield_strategy, 0, amount)?;
+
+    let vault = &mut ctx.accounts.vault;
+    vault.yield_deposited_balance = vault
+        .yield_deposited_balance
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    vault.available_balance = vault
+        .available_balance
+        .checked_add(received)
+        .ok_or(ErrorCode::Overflow)?;
+    vault.pending_redemption_balance = vault
+        .pending_redemption_balance
+        .checked_add(pending)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let expires_at = now
+        .checked_add(YIELD_REDEMPTION_TIMEOUT_SECONDS)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(YieldRedemptionRequestedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program: yp,
+        requested_amount: amount,
+        immediate_amount: received,
+        pending_amount: pending,
+        expires_at,
+    });
+
+    let redemption = &mut ctx.accounts.redemption;
+    if pending == 0 {
+        // Filled on the spot: no ticket to keep
+        return redemption.close(ctx.accounts.authority.to_account_info());
+    }
+    redemption.vault = vault.key();
+    redemption.yield_program = yp;
+    redemption.requested_amount = amount;
+    redemption.filled_amount = received;
+    redemption.requested_at = now;
+    redemption.expires_at = expires_at;
+    redemption.bump = ctx.bumps.redemption;
+
+    Ok(())
+}
+
+/// Collect filled redemptions for an open ticket. The adapter is asked to pay
+/// out and the measured amount moves from pending to available (partial fills
+/// leave the ticket open). From `expires_at` on, whatever is still unfilled
+/// after that claim is written down from `total_balance` and the ticket is
+/// closed.
+pub fn claim_yield_withdraw<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimYieldWithdraw<'info>>,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    ctx.accounts
+        .vault
+        .check_authority(ctx.accounts.authority.key(), ctx.remaining_accounts)?;
+
+    let yp = ctx.accounts.yield_program.key();
+    let now = Clock::get()?.unix_timestamp;
+    let timed_out = now >= ctx.accounts.redemption.expires_at;
+
+    // The adapter is asked to pay out even on timeout, so a late fill is still
+    // credited before the rest is written off
+    let vault_before = ctx.accounts.vault_token_account.amount;
+
+    let vault_seed = ctx.accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    invoke_adapter(
+        &ctx.accounts.yield_program.to_account_info(),
+        AdapterInstruction::ClaimWithdraw,
+        AdapterAccounts {
+            vault: ctx.accounts.vault.to_account_info(),
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            receipt_token_account: ctx.accounts.receipt_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        ctx.remaining_accounts,
+        signer,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    let claimed = ctx
+        .accounts
+        .vault_token_account
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    require!(
+        claimed <= ctx.accounts.redemption.remaining(),
+        ErrorCode::YieldAdapterMismatch
+    );
+
+    let redemption = &mut ctx.accounts.redemption;
+    let vault = &mut ctx.accounts.vault;
+    if claimed > 0 {
+        redemption.filled_amount = redemption
+            .filled_amount
+            .checked_add(claimed)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.pending_redemption_balance = vault
+            .pending_redemption_balance
+            .checked_sub(claimed)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.available_balance = vault
+            .available_balance
+            .checked_add(claimed)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    let remaining = redemption.remaining();
+    let written_off = if timed_out { remaining } else { 0 };
+    if written_off > 0 {
+        vault.pending_redemption_balance = vault
+            .pending_redemption_balance
+            .checked_sub(written_off)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.total_balance = vault
+            .total_balance
+            .checked_sub(written_off)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    emit!(YieldRedemptionClaimedEvent {
+        vault: vault.key(),
+        owner: vault.owner,
+        program: yp,
+        claimed_amount: claimed,
+        filled_amount: redemption.filled_amount,
+        remaining_amount: remaining,
+        timed_out,
+        timestamp: now,
+    });
+    if written_off > 0 {
+        emit!(YieldLossEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            program: yp,
+            principal_loss: written_off,
+            accrued_written_off: 0,
+            new_yield_balance: vault.yield_deposited_balance,
+            new_total_balance: vault.total_balance,
+            timestamp: now,
+        });
+    }
+
+    if remaining == 0 || timed_out {
+        redemption.close(ctx.accounts.authority.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestYieldWithdraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Owner used for PDA seeds
+    /// CHECK: seed/equality only
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+        seeds = [crate::constants::VAULT_AUTHORITY_SEED],
+        bump = vault_authority.bump,
+    )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// CHECK: key checked against yield_whitelist; must be executable
+    pub yield_program: UncheckedAccount<'info>,
+
+    /// Strategy record for cap accounting (may be uninitialized)
+    /// CHECK: address fixed by seeds; updated via YieldStrategy::record_flows
+    #[account(mut, seeds = [YIELD_STRATEGY_SEED, yield_program.key().as_ref()], bump)]
+    pub yield_strategy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Vault-owned token account holding the strategy's receipt tokens
+    #[account(
+        mut,
+        constraint = receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = receipt_token_account.key() != vault_token_account.key() @ ErrorCode::Unauthorized,
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump = yield_position.bump,
+        constraint = yield_position.receipt_token_account == receipt_token_account.key() @ ErrorCode::YieldAdapterMismatch,
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = YieldRedemption::LEN,
+        seeds = [YIELD_REDEMPTION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump
+    )]
+    pub redemption: Account<'info, YieldRedemption>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimYieldWithdraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Owner used for PDA seeds
+    /// CHECK: seed/equality only
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub vault: Account<'info, CollateralVault>,
+
+    /// The strategy the ticket was opened against; claims stay open even if it
+    /// has since been removed from the whitelist
+    /// CHECK: fixed by the redemption ticket seeds; must be executable
+    pub yield_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Vault-owned token account holding the strategy's receipt tokens
+    #[account(
+        mut,
+        constraint = receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = receipt_token_account.key() == yield_position.receipt_token_account @ ErrorCode::YieldAdapterMismatch,
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump = yield_position.bump,
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+
+    #[account(
+        mut,
+        seeds = [YIELD_REDEMPTION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump = redemption.bump,
+        has_one = vault @ ErrorCode::Unauthorized,
+    )]
+    pub redemption: Account<'info, YieldRedemption>,
+
+    pub token_program: Program<'info, Token>,
+}
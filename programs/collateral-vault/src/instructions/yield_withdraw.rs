@@ -1,14 +1,23 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
 
-use crate::constants::VAULT_SEED;
+use crate::constants::{VAULT_SEED, YIELD_POSITION_SEED, YIELD_STRATEGY_SEED};
 use crate::error::ErrorCode;
-use crate::events::{TransactionEvent, YieldWithdrawEvent};
-use crate::state::{CollateralVault, VaultAuthority};
+use crate::events::{TransactionEvent, YieldLossEvent, YieldWithdrawEvent};
+use crate::state::{CollateralVault, VaultAuthority, YieldPosition, YieldStrategy};
 use crate::types::TransactionType;
-use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
-use anchor_lang::solana_program::program::invoke_signed;
+use crate::yield_adapter::{invoke_adapter, AdapterAccounts, AdapterInstruction};
 
-pub fn handler(ctx: Context<YieldWithdraw>, amount: u64) -> Result<()> {
+#[allow(clippy::manual_contains)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, YieldWithdraw<'info>>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
     require!(amount > 0, ErrorCode::InvalidAmount);
 
     // Authorization: single-owner or multisig
@@ -51,6 +60,36 @@ pub fn handler(ctx: Context<YieldWithdraw>, amount: u64) -> Result<()> {
         require!(approved >= threshold, ErrorCode::Unauthorized);
     }
 
+    withdraw_from_strategy(ctx, amount)
+}
+
+/// Permissionless pull-back: when a lock left the vault below its liquid
+/// reserve, anyone may withdraw up to `yield_pullback_pending` from a strategy
+/// back into available balance.
+pub fn pull_back_yield<'info>(
+    ctx: Context<'_, '_, 'info, 'info, YieldWithdraw<'info>>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts.vault.record_owner_activity(
+        ctx.accounts.authority.key(),
+        ctx.remaining_accounts,
+        Clock::get()?.unix_timestamp,
+    );
+    let pending = ctx.accounts.vault.yield_pullback_pending;
+    require!(pending > 0, ErrorCode::InvalidAmount);
+    let amount = amount
+        .min(pending)
+        .min(ctx.accounts.yield_position.deposited);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    withdraw_from_strategy(ctx, amount)
+}
+
+#[allow(clippy::manual_contains)]
+fn withdraw_from_strategy<'info>(
+    ctx: Context<'_, '_, 'info, 'info, YieldWithdraw<'info>>,
+    amount: u64,
+) -> Result<()> {
     // Whitelist check for yield program (if provided)
     let yp = ctx.accounts.yield_program.key();
     require!(
@@ -63,27 +102,105 @@ pub fn handler(ctx: Context<YieldWithdraw>, amount: u64) -> Result<()> {
     );
 
     // Business invariants
-    let vault = &mut ctx.accounts.vault;
     require!(
-        vault.yield_deposited_balance >= amount,
+        ctx.accounts.yield_position.deposited >= amount,
         ErrorCode::InsufficientYieldBalance
     );
 
-    // Accounting: move funds from yield_deposited to available
+    // Route the withdrawal through the adapter and measure what actually moved
+    let vault_before = ctx.accounts.vault_token_account.amount;
+    let receipts_before = ctx.accounts.receipt_token_account.amount;
+
+    let vault_seed = ctx.accounts.vault.original_owner;
+    let signer_seeds: &[&[u8]] = &[VAULT_SEED, vault_seed.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    invoke_adapter(
+        &ctx.accounts.yield_program.to_account_info(),
+        AdapterInstruction::Withdraw { amount },
+        AdapterAccounts {
+            vault: ctx.accounts.vault.to_account_info(),
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            receipt_token_account: ctx.accounts.receipt_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        ctx.remaining_accounts,
+        signer,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.receipt_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .vault_token_account
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    let receipts_burned = receipts_before
+        .checked_sub(ctx.accounts.receipt_token_account.amount)
+        .ok_or(ErrorCode::YieldAdapterMismatch)?;
+    // The adapter may return less than requested, never more, and must burn receipts
+    require!(
+        received <= amount && receipts_burned > 0,
+        ErrorCode::YieldAdapterMismatch
+    );
+
+    // Principal the burned receipts stood for; unburned receipts keep backing the rest
+    let position = &mut ctx.accounts.yield_position;
+    let redeemed = position.principal_for_receipts(receipts_burned, receipts_before);
+
+    // Accounting: move the measured amount from yield_deposited to available
+    position.deposited = position
+        .deposited
+        .checked_sub(received)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let vault = &mut ctx.accounts.vault;
     vault.yield_deposited_balance = vault
         .yield_deposited_balance
-        .checked_sub(amount)
+        .checked_sub(received)
         .ok_or(ErrorCode::Overflow)?;
     vault.available_balance = vault
         .available_balance
-        .checked_add(amount)
+        .checked_add(received)
         .ok_or(ErrorCode::Overflow)?;
 
+    // Loss recognition: only principal behind burned receipts that did not come
+    // back is written down; a partial-liquidity exit leaves the rest deployed
+    let shortfall = redeemed.saturating_sub(received);
+    let (principal_loss, _) = position.write_down(vault, shortfall, false)?;
+    let outflow = received
+        .checked_add(principal_loss)
+        .ok_or(ErrorCode::Overflow)?;
+    YieldStrategy::record_flows(&ctx.accounts.yield_strategy, 0, outflow)?;
+
+    // Settle any pull-back request; it is cleared once the reserve is restored
+    vault.yield_pullback_pending = vault.yield_pullback_pending.saturating_sub(received);
+    if vault.available_balance >= vault.liquid_reserve_target() {
+        vault.yield_pullback_pending = 0;
+    }
+
+    // Invariant: total = locked + available + timelocked + yield deployed + pending redemptions
+    vault.check_balance_invariant()?;
+
+    if principal_loss > 0 {
+        emit!(YieldLossEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            program: yp,
+            principal_loss,
+            accrued_written_off: 0,
+            new_yield_balance: vault.yield_deposited_balance,
+            new_total_balance: vault.total_balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+
     emit!(YieldWithdrawEvent {
         vault: vault.key(),
         owner: vault.owner,
         program: yp,
-        amount,
+        amount: received,
+        receipt_amount: receipts_burned,
         new_yield_balance: vault.yield_deposited_balance,
         new_available_balance: vault.available_balance,
     });
@@ -92,36 +209,10 @@ pub fn handler(ctx: Context<YieldWithdraw>, amount: u64) -> Result<()> {
         vault: vault.key(),
         owner: vault.owner,
         transaction_type: TransactionType::YieldWithdraw,
-        amount,
+        amount: received,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
-    // Optional generic CPI passthrough (see yield_deposit): run external ix with PDA signer
-    let signer_seeds: &[&[u8]] = &[
-        crate::constants::VAULT_SEED,
-        ctx.accounts.vault.owner.as_ref(),
-        &[ctx.accounts.vault.bump],
-    ];
-    let signer: &[&[&[u8]]] = &[signer_seeds];
-    let remaining = ctx.remaining_accounts;
-    if !remaining.is_empty() {
-        let metas: Vec<AccountMeta> = remaining
-            .iter()
-            .map(|ai| AccountMeta {
-                pubkey: ai.key(),
-                is_signer: ai.is_signer || ai.key() == ctx.accounts.vault.key(),
-                is_writable: ai.is_writable,
-            })
-            .collect();
-        let program_id = ctx.accounts.yield_program.key();
-        let ix = Instruction {
-            program_id,
-            accounts: metas,
-            data: vec![],
-        };
-        let _ = invoke_signed(&ix, remaining, signer);
-    }
-
     Ok(())
 }
 
@@ -136,7 +227,7 @@ pub struct YieldWithdraw<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, owner.key().as_ref()],
+        seeds = [VAULT_SEED, vault.original_owner.as_ref()],
         bump = vault.bump,
         constraint = vault.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
@@ -150,6 +241,36 @@ pub struct YieldWithdraw<'info> {
     pub vault_authority: Account<'info, VaultAuthority>,
 
     /// The yield program to withdraw from (whitelisted)
-    /// CHECK: used for key only
+    /// CHECK: key checked against yield_whitelist; must be executable
     pub yield_program: UncheckedAccount<'info>,
+
+    /// Strategy record for cap accounting (may be uninitialized)
+    /// CHECK: address fixed by seeds; updated via YieldStrategy::record_flows
+    #[account(mut, seeds = [YIELD_STRATEGY_SEED, yield_program.key().as_ref()], bump)]
+    pub yield_strategy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = vault_token_account.mint == vault.usdt_mint @ ErrorCode::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Vault-owned token account holding the strategy's receipt tokens
+    #[account(
+        mut,
+        constraint = receipt_token_account.owner == vault.key() @ ErrorCode::Unauthorized,
+        constraint = receipt_token_account.key() != vault_token_account.key() @ ErrorCode::Unauthorized,
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [YIELD_POSITION_SEED, vault.key().as_ref(), yield_program.key().as_ref()],
+        bump = yield_position.bump,
+        constraint = yield_position.receipt_token_account == receipt_token_account.key() @ ErrorCode::YieldAdapterMismatch,
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+
+    pub token_program: Program<'info, Token>,
 }
@@ -18,12 +18,15 @@
 
 use anchor_lang::prelude::*;
 
+pub mod attestation;
 pub mod constants;
 pub mod error;
 pub mod events;
 pub mod instructions;
+pub mod oracle;
 pub mod state;
 pub mod types;
+pub mod yield_adapter;
 
 #[allow(ambiguous_glob_reexports, hidden_glob_reexports)]
 pub use instructions::*;
@@ -67,8 +70,24 @@ pub mod collateral_vault {
         instructions::multisig::disable_vault_multisig(ctx)
     }
 
-    pub fn lock_collateral(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
-        instructions::lock_collateral::handler(ctx, amount)
+    pub fn lock_collateral(
+        ctx: Context<LockCollateral>,
+        amount: u64,
+        lease_seconds: Option<i64>,
+    ) -> Result<()> {
+        instructions::lock_collateral::handler(ctx, amount, lease_seconds)
+    }
+
+    pub fn renew_lock(ctx: Context<RenewLock>, lease_seconds: i64) -> Result<()> {
+        instructions::lock_lease::renew_lock(ctx, lease_seconds)
+    }
+
+    pub fn release_expired_lock(ctx: Context<ReleaseExpiredLock>) -> Result<()> {
+        instructions::lock_lease::release_expired_lock(ctx)
+    }
+
+    pub fn check_lock_lease(ctx: Context<CheckLockLease>, program: Pubkey) -> Result<()> {
+        instructions::lock_lease::check_lock_lease(ctx, program)
     }
 
     pub fn unlock_collateral(ctx: Context<UnlockCollateral>, amount: u64) -> Result<()> {
@@ -79,6 +98,13 @@ pub mod collateral_vault {
         instructions::transfer_collateral::handler(ctx, amount)
     }
 
+    pub fn batch_settle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSettle<'info>>,
+        entries: Vec<types::SettlementEntry>,
+    ) -> Result<()> {
+        instructions::batch_settle::handler(ctx, entries)
+    }
+
     pub fn schedule_timelock(
         ctx: Context<ScheduleTimelock>,
         amount: u64,
@@ -95,46 +121,217 @@ pub mod collateral_vault {
         instructions::request_withdraw::handler(ctx, amount)
     }
 
-    pub fn set_withdraw_min_delay(ctx: Context<UpdatePolicy>, seconds: i64) -> Result<()> {
+    pub fn set_withdraw_min_delay(ctx: Context<UpdateSecurityPolicy>, seconds: i64) -> Result<()> {
         instructions::withdraw_policy::set_min_delay(ctx, seconds)
     }
 
     pub fn set_withdraw_rate_limit(
-        ctx: Context<UpdatePolicy>,
+        ctx: Context<UpdateSecurityPolicy>,
         window_seconds: u32,
         max_amount: u64,
     ) -> Result<()> {
         instructions::withdraw_policy::set_rate_limit(ctx, window_seconds, max_amount)
     }
 
-    pub fn add_withdraw_whitelist(ctx: Context<UpdatePolicy>, address: Pubkey) -> Result<()> {
+    pub fn add_withdraw_whitelist(
+        ctx: Context<UpdateSecurityPolicy>,
+        address: Pubkey,
+    ) -> Result<()> {
         instructions::withdraw_policy::add_whitelist(ctx, address)
     }
 
-    pub fn remove_withdraw_whitelist(ctx: Context<UpdatePolicy>, address: Pubkey) -> Result<()> {
+    pub fn remove_withdraw_whitelist(ctx: Context<RestrictPolicy>, address: Pubkey) -> Result<()> {
         instructions::withdraw_policy::remove_whitelist(ctx, address)
     }
 
+    pub fn apply_policy_changes(ctx: Context<UpdatePolicy>) -> Result<()> {
+        instructions::policy_changes::apply_policy_changes(ctx)
+    }
+
+    pub fn cancel_policy_change(ctx: Context<RestrictPolicy>, index: u8) -> Result<()> {
+        instructions::policy_changes::cancel_policy_change(ctx, index)
+    }
+
+    pub fn set_liquid_reserve(
+        ctx: Context<UpdatePolicy>,
+        min_liquid_reserve_bps: u16,
+    ) -> Result<()> {
+        instructions::withdraw_policy::set_liquid_reserve(ctx, min_liquid_reserve_bps)
+    }
+
+    pub fn approve_integrator(
+        ctx: Context<UpdatePolicy>,
+        program: Pubkey,
+        max_locked: Option<u64>,
+    ) -> Result<()> {
+        instructions::integrators::approve_integrator(ctx, program, max_locked)
+    }
+
+    pub fn revoke_integrator(ctx: Context<RestrictPolicy>, program: Pubkey) -> Result<()> {
+        instructions::integrators::revoke_integrator(ctx, program)
+    }
+
+    pub fn set_auto_compound_opt_out(ctx: Context<UpdatePolicy>, opt_out: bool) -> Result<()> {
+        instructions::withdraw_policy::set_auto_compound_opt_out(ctx, opt_out)
+    }
+
+    pub fn set_strict_withdrawals(ctx: Context<UpdatePolicy>, strict: bool) -> Result<()> {
+        instructions::withdraw_policy::set_strict_withdrawals(ctx, strict)
+    }
+
+    pub fn propose_co_signer_policy(
+        ctx: Context<UpdatePolicy>,
+        policy: Option<types::CoSignerPolicy>,
+    ) -> Result<()> {
+        instructions::co_signer::propose_co_signer_policy(ctx, policy)
+    }
+
+    pub fn apply_co_signer_policy(ctx: Context<UpdatePolicy>) -> Result<()> {
+        instructions::co_signer::apply_co_signer_policy(ctx)
+    }
+
+    pub fn cancel_co_signer_policy(ctx: Context<RestrictPolicy>) -> Result<()> {
+        instructions::co_signer::cancel_co_signer_policy(ctx)
+    }
+
+    pub fn panic_lock_vault(ctx: Context<PanicLock>) -> Result<()> {
+        instructions::panic_lock::panic_lock_vault(ctx)
+    }
+
+    pub fn request_panic_unlock(ctx: Context<PanicLock>) -> Result<()> {
+        instructions::panic_lock::request_panic_unlock(ctx)
+    }
+
+    pub fn unlock_vault(ctx: Context<PanicLock>) -> Result<()> {
+        instructions::panic_lock::unlock_vault(ctx)
+    }
+
+    pub fn propose_guardians(
+        ctx: Context<UpdatePolicy>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::recovery::propose_guardians(ctx, guardians, threshold)
+    }
+
+    pub fn apply_guardians(ctx: Context<UpdatePolicy>) -> Result<()> {
+        instructions::recovery::apply_guardians(ctx)
+    }
+
+    pub fn cancel_guardian_change(ctx: Context<RestrictPolicy>) -> Result<()> {
+        instructions::recovery::cancel_guardian_change(ctx)
+    }
+
+    pub fn start_recovery(ctx: Context<GuardianRecovery>, new_owner: Pubkey) -> Result<()> {
+        instructions::recovery::start_recovery(ctx, new_owner)
+    }
+
+    pub fn veto_recovery(ctx: Context<GuardianRecovery>) -> Result<()> {
+        instructions::recovery::veto_recovery(ctx)
+    }
+
+    pub fn complete_recovery(ctx: Context<GuardianRecovery>) -> Result<()> {
+        instructions::recovery::complete_recovery(ctx)
+    }
+
+    pub fn set_beneficiary(
+        ctx: Context<UpdatePolicy>,
+        beneficiary: Option<Pubkey>,
+        inactivity_period_seconds: i64,
+    ) -> Result<()> {
+        instructions::inheritance::set_beneficiary(ctx, beneficiary, inactivity_period_seconds)
+    }
+
+    pub fn claim_inheritance(ctx: Context<ClaimInheritance>) -> Result<()> {
+        instructions::inheritance::claim_inheritance(ctx)
+    }
+
+    pub fn migrate_vault_authority(ctx: Context<MigrateVaultAuthority>) -> Result<()> {
+        instructions::migrate::migrate_vault_authority(ctx)
+    }
+
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        instructions::migrate::migrate_vault(ctx)
+    }
+
     pub fn initialize_vault_authority(
         ctx: Context<InitializeVaultAuthority>,
-        authorized_programs: Vec<Pubkey>,
         freeze: Option<bool>,
     ) -> Result<()> {
-        instructions::authority::initialize_vault_authority(ctx, authorized_programs, freeze)
+        instructions::authority::initialize_vault_authority(ctx, freeze)
     }
 
-    pub fn add_authorized_program(
-        ctx: Context<UpdateVaultAuthority>,
+    pub fn register_integrator(
+        ctx: Context<RegisterIntegrator>,
         program: Pubkey,
+        capabilities: u8,
+        max_total_locked: Option<u64>,
+        name: String,
+        version: u32,
+    ) -> Result<()> {
+        instructions::integrator_registry::register_integrator(
+            ctx,
+            program,
+            capabilities,
+            max_total_locked,
+            name,
+            version,
+        )
+    }
+
+    pub fn update_integrator(
+        ctx: Context<UpdateIntegrator>,
+        capabilities: u8,
+        enabled: bool,
+        max_total_locked: Option<u64>,
+        name: String,
+        version: u32,
+    ) -> Result<()> {
+        instructions::integrator_registry::update_integrator(
+            ctx,
+            capabilities,
+            enabled,
+            max_total_locked,
+            name,
+            version,
+        )
+    }
+
+    pub fn set_risk_signer(
+        ctx: Context<UpdateIntegrator>,
+        risk_signer: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::integrator_registry::set_risk_signer(ctx, risk_signer)
+    }
+
+    pub fn deprecate_integrator(
+        ctx: Context<UpdateIntegrator>,
+        grace_period_seconds: i64,
+    ) -> Result<()> {
+        instructions::wind_down::deprecate_integrator(ctx, grace_period_seconds)
+    }
+
+    pub fn reclaim_stranded_lock(ctx: Context<ReclaimStrandedLock>) -> Result<()> {
+        instructions::wind_down::reclaim_stranded_lock(ctx)
+    }
+
+    pub fn settle_stranded_locks<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleStrandedLocks<'info>>,
     ) -> Result<()> {
-        instructions::authority::add_authorized_program(ctx, program)
+        instructions::wind_down::settle_stranded_locks(ctx)
     }
 
-    pub fn remove_authorized_program(
+    pub fn release_unattributed_locks<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseUnattributedLocks<'info>>,
+    ) -> Result<()> {
+        instructions::wind_down::release_unattributed_locks(ctx)
+    }
+
+    pub fn set_lock_lease_grace(
         ctx: Context<UpdateVaultAuthority>,
-        program: Pubkey,
+        grace_seconds: i64,
     ) -> Result<()> {
-        instructions::authority::remove_authorized_program(ctx, program)
+        instructions::authority::set_lock_lease_grace(ctx, grace_seconds)
     }
 
     pub fn set_freeze_flag(ctx: Context<UpdateVaultAuthority>, freeze: bool) -> Result<()> {
@@ -145,6 +342,20 @@ pub mod collateral_vault {
         instructions::authority::set_cpi_enforced(ctx, cpi_enforced)
     }
 
+    pub fn set_policy_change_delay_floor(
+        ctx: Context<UpdateVaultAuthority>,
+        seconds: i64,
+    ) -> Result<()> {
+        instructions::authority::set_policy_change_delay_floor(ctx, seconds)
+    }
+
+    pub fn set_free_margin_withdrawals(
+        ctx: Context<UpdateVaultAuthority>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::authority::set_free_margin_withdrawals(ctx, enabled)
+    }
+
     pub fn add_yield_program(ctx: Context<UpdateVaultAuthority>, program: Pubkey) -> Result<()> {
         instructions::authority::add_yield_program(ctx, program)
     }
@@ -157,6 +368,76 @@ pub mod collateral_vault {
         instructions::authority::set_risk_level(ctx, risk_level)
     }
 
+    pub fn set_risk_params(
+        ctx: Context<UpdateVaultAuthority>,
+        level: u8,
+        params: types::RiskParams,
+    ) -> Result<()> {
+        instructions::authority::set_risk_params(ctx, level, params)
+    }
+
+    pub fn set_yield_strategy_impaired(
+        ctx: Context<UpdateYieldStrategy>,
+        impaired: bool,
+    ) -> Result<()> {
+        instructions::authority::set_yield_strategy_impaired(ctx, impaired)
+    }
+
+    pub fn set_yield_strategy_caps(
+        ctx: Context<UpdateYieldStrategy>,
+        max_deposit: u64,
+        max_tvl_share_bps: u16,
+    ) -> Result<()> {
+        instructions::authority::set_yield_strategy_caps(ctx, max_deposit, max_tvl_share_bps)
+    }
+
+    pub fn set_compound_config(
+        ctx: Context<UpdateVaultAuthority>,
+        compound_interval_seconds: i64,
+        performance_fee_bps: u16,
+        keeper_bounty_bps: u16,
+    ) -> Result<()> {
+        instructions::authority::set_compound_config(
+            ctx,
+            compound_interval_seconds,
+            performance_fee_bps,
+            keeper_bounty_bps,
+        )
+    }
+
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        instructions::treasury::initialize_treasury(ctx)
+    }
+
+    pub fn set_fee_schedule(
+        ctx: Context<UpdateVaultAuthority>,
+        operation: types::FeeOperation,
+        schedule: types::FeeSchedule,
+    ) -> Result<()> {
+        instructions::authority::set_fee_schedule(ctx, operation, schedule)
+    }
+
+    pub fn sweep_treasury(ctx: Context<SweepTreasury>, amount: u64) -> Result<()> {
+        instructions::treasury::sweep_treasury(ctx, amount)
+    }
+
+    pub fn set_custody_fee(
+        ctx: Context<UpdateVaultAuthority>,
+        custody_fee_bps: u16,
+        exempt_tiers: u8,
+    ) -> Result<()> {
+        instructions::authority::set_custody_fee(ctx, custody_fee_bps, exempt_tiers)
+    }
+
+    /// Accrue and collect a vault's custody fee (permissionless)
+    pub fn accrue_fees(ctx: Context<AccrueFees>) -> Result<()> {
+        instructions::accrue_fees::handler(ctx)
+    }
+
+    pub fn set_vault_risk_tier(ctx: Context<SetVaultRiskTier>, tier: Option<u8>) -> Result<()> {
+        instructions::authority::set_vault_risk_tier(ctx, tier)
+    }
+
     pub fn add_delegate(ctx: Context<UpdateDelegates>, delegate: Pubkey) -> Result<()> {
         instructions::delegation::add_delegate(ctx, delegate)
     }
@@ -178,17 +459,94 @@ pub mod collateral_vault {
         Ok(())
     }
 
-    pub fn yield_deposit(ctx: Context<YieldDeposit>, amount: u64) -> Result<()> {
+    pub fn set_collateral_config(
+        ctx: Context<SetCollateralConfig>,
+        haircut_bps: u16,
+        max_staleness_seconds: i64,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        instructions::collateral_config::set_collateral_config(
+            ctx,
+            haircut_bps,
+            max_staleness_seconds,
+            max_confidence_bps,
+        )
+    }
+
+    /// Haircut-adjusted USD value of the vault (read-only, returned via return data)
+    pub fn get_collateral_value(
+        ctx: Context<GetCollateralValue>,
+    ) -> Result<types::CollateralValuation> {
+        instructions::get_collateral_value::handler(ctx)
+    }
+
+    pub fn yield_deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, YieldDeposit<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         instructions::yield_deposit::handler(ctx, amount)
     }
 
-    pub fn yield_withdraw(ctx: Context<YieldWithdraw>, amount: u64) -> Result<()> {
+    pub fn yield_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, YieldWithdraw<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         instructions::yield_withdraw::handler(ctx, amount)
     }
 
-    pub fn compound_yield(ctx: Context<CompoundYield>, compounded_amount: u64) -> Result<()> {
+    pub fn pull_back_yield<'info>(
+        ctx: Context<'_, '_, 'info, 'info, YieldWithdraw<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::yield_withdraw::pull_back_yield(ctx, amount)
+    }
+
+    pub fn compound_yield<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompoundYield<'info>>,
+        compounded_amount: u64,
+    ) -> Result<()> {
         instructions::compound_yield::handler(ctx, compounded_amount)
     }
+
+    pub fn crank_compound<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankCompound<'info>>,
+    ) -> Result<()> {
+        instructions::crank_compound::crank_compound(ctx)
+    }
+
+    pub fn harvest_yield<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestYield<'info>>,
+    ) -> Result<()> {
+        instructions::harvest_yield::handler(ctx)
+    }
+
+    pub fn set_yield_target_weight(
+        ctx: Context<SetYieldTargetWeight>,
+        target_weight_bps: u16,
+    ) -> Result<()> {
+        instructions::rebalance_yield::set_yield_target_weight(ctx, target_weight_bps)
+    }
+
+    pub fn rebalance_yield<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RebalanceYield<'info>>,
+        amount: u64,
+        from_extra_accounts: u8,
+    ) -> Result<()> {
+        instructions::rebalance_yield::rebalance_yield(ctx, amount, from_extra_accounts)
+    }
+
+    pub fn request_yield_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RequestYieldWithdraw<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::yield_redemption::request_yield_withdraw(ctx, amount)
+    }
+
+    pub fn claim_yield_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimYieldWithdraw<'info>>,
+    ) -> Result<()> {
+        instructions::yield_redemption::claim_yield_withdraw(ctx)
+    }
 }
 
 #[derive(Accounts)]
@@ -204,17 +562,85 @@ pub struct Initialize<'info> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constants::MIN_DEPOSIT;
-    use crate::state::{CollateralVault, VaultAuthority};
-    use crate::types::{PendingWithdrawalEntry, TimelockEntry};
+    use crate::constants::{
+        CO_SIGNER_POLICY_DELAY_SECONDS, GUARDIAN_CHANGE_DELAY_SECONDS, MAX_FEE_BPS,
+        MAX_LOCK_LEASE_SECONDS, MIN_DEPOSIT, MIN_INACTIVITY_PERIOD_SECONDS,
+        PANIC_UNLOCK_COOLDOWN_SECONDS, RECOVERY_DELAY_SECONDS, SECONDS_PER_YEAR,
+    };
+    use crate::state::{CollateralVault, IntegratorRegistry, VaultAuthority};
+    use crate::types::{
+        CoSignerPolicy, FeeSchedule, IntegratorApproval, PendingWithdrawalEntry, PolicyChange,
+        RiskParams, TimelockEntry,
+    };
+
+    fn sample_vault() -> CollateralVault {
+        let owner = Pubkey::new_unique();
+        CollateralVault {
+            owner,
+            token_account: Pubkey::new_unique(),
+            usdt_mint: Pubkey::new_unique(),
+            total_balance: 123,
+            locked_balance: 45,
+            available_balance: 78,
+            total_deposited: 1000,
+            total_withdrawn: 800,
+            yield_deposited_balance: 0,
+            yield_accrued_balance: 0,
+            last_compounded_at: 0,
+            active_yield_program: Pubkey::default(),
+            last_harvested_at: 0,
+            yield_target_weight_bps: 6_000,
+            pending_redemption_balance: 0,
+            min_liquid_reserve_bps: 0,
+            yield_pullback_pending: 0,
+            auto_compound_opt_out: false,
+            strict_withdrawals: false,
+            last_fee_accrual_at: 0,
+            custody_fee_index_snapshot: 0,
+            custody_fee_owed: 0,
+            created_at: 1_700_000_000,
+            bump: 254,
+            original_owner: owner,
+            multisig_threshold: 0,
+            multisig_signers: vec![],
+            delegates: vec![],
+            timelocks: vec![],
+            min_withdraw_delay_seconds: 0,
+            pending_withdrawals: vec![],
+            withdraw_whitelist: vec![],
+            rate_window_seconds: 0,
+            rate_limit_amount: 0,
+            last_withdrawal_window_start: 0,
+            withdrawn_in_window: 0,
+            risk_tier_override: Some(2),
+            integrators: vec![],
+            co_signer: None,
+            pending_co_signer: None,
+            co_signer_nonce: 0,
+            pending_policy_changes: vec![],
+            panic_locked_at: 0,
+            panic_unlock_requested_at: 0,
+            guardians: vec![],
+            guardian_threshold: 0,
+            pending_guardians: None,
+            pending_recovery: None,
+            beneficiary: None,
+            inactivity_period_seconds: 0,
+            last_owner_activity_at: 0,
+            layout_version: 0,
+            _reserved: [0u8; 63],
+        }
+    }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn min_deposit_constant_is_positive() {
         assert!(MIN_DEPOSIT > 0, "MIN_DEPOSIT must be > 0 for deposit validation");
         assert_eq!(MIN_DEPOSIT, 1, "MIN_DEPOSIT should be 1 per constants");
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants, clippy::identity_op)]
     fn collateral_vault_len_is_consistent() {
         assert!(
             CollateralVault::LEN >= 8 + 32 * 4 + 8 * 14 + 1 * 2 + 64,
@@ -248,6 +674,205 @@ mod tests {
         assert_eq!(e.executable_at, back.executable_at);
     }
 
+    #[test]
+    fn net_settlement_matches_payers_to_receivers() {
+        use crate::instructions::batch_settle::net_settlement;
+
+        let transfers = net_settlement(&[-100, 60, -20, 40, 20]).unwrap();
+        assert!(transfers.len() <= 4);
+
+        let mut net = [0i64; 5];
+        for (from, to, amount) in transfers.iter() {
+            net[*from] -= *amount as i64;
+            net[*to] += *amount as i64;
+        }
+        assert_eq!(net, [-100, 60, -20, 40, 20]);
+    }
+
+    #[test]
+    fn net_settlement_rejects_unbalanced_deltas() {
+        use crate::instructions::batch_settle::net_settlement;
+
+        assert!(net_settlement(&[-100, 60]).is_err());
+        assert!(net_settlement(&[0, 0]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn price_feed_pack_unpack_roundtrip() {
+        use crate::oracle::{PriceFeed, PRICE_FEED_LEN};
+
+        let feed = PriceFeed {
+            expo: -8,
+            price: 15_000_000_000,
+            conf: 5_000_000,
+            publish_time: 1_700_000_000,
+        };
+        let mut data = [0u8; PRICE_FEED_LEN];
+        feed.pack_into(&mut data).unwrap();
+        assert!(PriceFeed::unpack(&data).unwrap() == feed);
+
+        // Corrupted magic is rejected
+        data[0] ^= 0xff;
+        assert!(PriceFeed::unpack(&data).is_err());
+    }
+
+    #[test]
+    fn price_feed_staleness_confidence_and_value() {
+        use crate::oracle::{apply_haircut, PriceFeed};
+
+        // 150.00 USD with a 0.05 USD confidence interval
+        let feed = PriceFeed {
+            expo: -8,
+            price: 15_000_000_000,
+            conf: 5_000_000,
+            publish_time: 1_000,
+        };
+        assert!(feed.validate(1_030, 60, 10).is_ok());
+        assert!(feed.validate(1_061, 60, 10).is_err());
+        assert!(feed.validate(1_030, 60, 1).is_err());
+        // Publish times ahead of the clock only pass within the skew tolerance
+        assert!(feed.validate(995, 60, 10).is_ok());
+        assert!(feed.validate(994, 60, 10).is_err());
+
+        // 2.5 tokens of a 9-decimal mint => 375 USD (6 decimals)
+        let value = feed.usd_value(2_500_000_000, 9).unwrap();
+        assert_eq!(value, 375_000_000);
+        // 20% haircut
+        assert_eq!(apply_haircut(value, 2_000).unwrap(), 300_000_000);
+    }
+
+    #[test]
+    fn yield_adapter_instruction_encoding() {
+        use crate::yield_adapter::{
+            AdapterInstruction, ADAPTER_CLAIM_WITHDRAW_DISCRIMINATOR,
+            ADAPTER_DEPOSIT_DISCRIMINATOR, ADAPTER_HARVEST_DISCRIMINATOR,
+            ADAPTER_POSITION_VALUE_DISCRIMINATOR, ADAPTER_REQUEST_WITHDRAW_DISCRIMINATOR,
+            ADAPTER_WITHDRAW_DISCRIMINATOR,
+        };
+
+        let data = AdapterInstruction::Deposit { amount: 42 }.data();
+        assert_eq!(&data[..8], &ADAPTER_DEPOSIT_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(data[8..16].try_into().unwrap()), 42);
+
+        let data = AdapterInstruction::Withdraw { amount: 7 }.data();
+        assert_eq!(&data[..8], &ADAPTER_WITHDRAW_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(data[8..16].try_into().unwrap()), 7);
+
+        assert_eq!(
+            AdapterInstruction::Harvest.data(),
+            ADAPTER_HARVEST_DISCRIMINATOR.to_vec()
+        );
+        assert_eq!(
+            AdapterInstruction::PositionValue.data(),
+            ADAPTER_POSITION_VALUE_DISCRIMINATOR.to_vec()
+        );
+
+        let data = AdapterInstruction::RequestWithdraw { amount: 9 }.data();
+        assert_eq!(&data[..8], &ADAPTER_REQUEST_WITHDRAW_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(data[8..16].try_into().unwrap()), 9);
+        assert_eq!(
+            AdapterInstruction::ClaimWithdraw.data(),
+            ADAPTER_CLAIM_WITHDRAW_DISCRIMINATOR.to_vec()
+        );
+    }
+
+    #[test]
+    fn yield_position_binds_once_and_computes_targets() {
+        use crate::state::YieldPosition;
+
+        let vault = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let receipt = Pubkey::new_unique();
+        let mut position = YieldPosition {
+            vault: Pubkey::default(),
+            yield_program: Pubkey::default(),
+            receipt_token_account: Pubkey::default(),
+            deposited: 0,
+            accrued: 0,
+            target_weight_bps: 2_500,
+            last_harvested_at: 0,
+            bump: 0,
+            _reserved: [0u8; 32],
+        };
+        position.bind(vault, program, None, 254).unwrap();
+        position.bind(vault, program, Some(receipt), 254).unwrap();
+        assert_eq!(position.receipt_token_account, receipt);
+        assert!(position
+            .bind(vault, program, Some(Pubkey::new_unique()), 254)
+            .is_err());
+        assert!(position
+            .bind(vault, Pubkey::new_unique(), None, 254)
+            .is_err());
+        assert_eq!(position.target_amount(1_000_000), 250_000);
+
+        // Write-down: accrued absorbs the shortfall first, then principal and total
+        let mut vault = sample_vault();
+        position.deposited = 100;
+        position.accrued = 10;
+        vault.yield_deposited_balance = 100;
+        vault.yield_accrued_balance = 10;
+        vault.total_balance = vault.locked_balance + vault.available_balance + 100;
+        assert_eq!(position.write_down(&mut vault, 25, true).unwrap(), (15, 10));
+        assert_eq!(position.deposited, 85);
+        assert_eq!(vault.yield_accrued_balance, 0);
+        assert!(vault.check_balance_invariant().is_ok());
+        assert_eq!(position.write_down(&mut vault, 5, false).unwrap(), (5, 0));
+        assert!(vault.check_balance_invariant().is_ok());
+        assert!(position.write_down(&mut vault, 81, false).is_err());
+
+        // Burned receipts are valued pro rata against the remaining principal
+        position.deposited = 80;
+        assert_eq!(position.principal_for_receipts(25, 100), 20);
+        assert_eq!(position.principal_for_receipts(150, 100), 80);
+        assert_eq!(position.principal_for_receipts(1, 0), 0);
+    }
+
+    #[test]
+    fn yield_strategy_caps_and_liquid_reserve() {
+        use crate::state::YieldStrategy;
+
+        let mut strategy = YieldStrategy {
+            yield_program: Pubkey::new_unique(),
+            impaired: false,
+            impaired_updated_at: 0,
+            max_deposit: 0,
+            max_tvl_share_bps: 0,
+            total_deposited: 0,
+            bump: 0,
+            _reserved: [0u8; 32],
+        };
+        // No caps configured
+        assert!(strategy.check_capacity(u64::MAX, 0).is_ok());
+
+        strategy.max_deposit = 500;
+        strategy.max_tvl_share_bps = 2_000; // 20% of TVL
+        assert!(strategy.check_capacity(200, 1_000).is_ok());
+        assert!(strategy.check_capacity(201, 1_000).is_err());
+        assert!(strategy.check_capacity(501, 10_000).is_err());
+
+        strategy.apply_flows(300, 0).unwrap();
+        strategy.apply_flows(0, 500).unwrap();
+        assert_eq!(strategy.total_deposited, 0);
+
+        let mut vault = sample_vault();
+        vault.total_balance = 1_000;
+        vault.min_liquid_reserve_bps = 2_500;
+        assert_eq!(vault.liquid_reserve_target(), 250);
+    }
+
+    #[test]
+    fn realized_apr_annualizes_gain_over_period() {
+        use crate::constants::SECONDS_PER_YEAR;
+        use crate::instructions::harvest_yield::realized_apr_bps;
+
+        // 5% over a full year
+        assert_eq!(realized_apr_bps(50, 1_000, SECONDS_PER_YEAR), 500);
+        // 1% over a quarter annualizes to 4%
+        assert_eq!(realized_apr_bps(10, 1_000, SECONDS_PER_YEAR / 4), 400);
+        assert_eq!(realized_apr_bps(10, 0, SECONDS_PER_YEAR), 0);
+        assert_eq!(realized_apr_bps(10, 1_000, 0), 0);
+    }
+
     #[test]
     fn collateral_vault_serde_roundtrip() {
         let mut vault = CollateralVault {
@@ -263,8 +888,19 @@ mod tests {
             yield_accrued_balance: 0,
             last_compounded_at: 0,
             active_yield_program: Pubkey::default(),
+            last_harvested_at: 0,
+            yield_target_weight_bps: 0,
+            pending_redemption_balance: 0,
+            min_liquid_reserve_bps: 0,
+            yield_pullback_pending: 0,
+            auto_compound_opt_out: false,
+            strict_withdrawals: false,
+            last_fee_accrual_at: 0,
+            custody_fee_index_snapshot: 0,
+            custody_fee_owed: 0,
             created_at: 1_700_000_000,
             bump: 254,
+            original_owner: Pubkey::default(),
             multisig_threshold: 0,
             multisig_signers: vec![],
             delegates: vec![],
@@ -276,7 +912,23 @@ mod tests {
             rate_limit_amount: 0,
             last_withdrawal_window_start: 0,
             withdrawn_in_window: 0,
-            _reserved: [0u8; 64],
+            risk_tier_override: None,
+            integrators: vec![],
+            co_signer: None,
+            pending_co_signer: None,
+            co_signer_nonce: 0,
+            pending_policy_changes: vec![],
+            panic_locked_at: 0,
+            panic_unlock_requested_at: 0,
+            guardians: vec![],
+            guardian_threshold: 0,
+            pending_guardians: None,
+            pending_recovery: None,
+            beneficiary: None,
+            inactivity_period_seconds: 0,
+            last_owner_activity_at: 0,
+            layout_version: 0,
+            _reserved: [0u8; 63],
         };
 
         vault.total_balance = vault.locked_balance + vault.available_balance;
@@ -295,6 +947,28 @@ mod tests {
         assert_eq!(vault.bump, back.bump);
     }
 
+    #[test]
+    fn balance_invariant_counts_timelocked_and_yield_funds() {
+        let mut vault = sample_vault();
+        vault.total_balance = vault.locked_balance + vault.available_balance;
+        assert!(vault.check_balance_invariant().is_ok());
+
+        // Timelocked and yield-deployed funds still count towards total_balance
+        vault.timelocks.push(TimelockEntry {
+            amount: 10,
+            unlock_time: 0,
+        });
+        vault.yield_deposited_balance = 5;
+        assert!(vault.check_balance_invariant().is_err());
+        vault.total_balance += 15;
+        assert!(vault.check_balance_invariant().is_ok());
+        // So do redemptions requested from a strategy but not yet paid back
+        vault.pending_redemption_balance = 7;
+        assert!(vault.check_balance_invariant().is_err());
+        vault.total_balance += 7;
+        assert!(vault.check_balance_invariant().is_ok());
+    }
+
     #[test]
     fn vault_authority_serde_roundtrip() {
         let governance = Pubkey::new_unique();
@@ -302,20 +976,835 @@ mod tests {
 
         let va = VaultAuthority {
             governance,
-            authorized_programs: programs.clone(),
             bump: 200,
             freeze: false,
             cpi_enforced: false,
             yield_whitelist: programs.clone(),
             risk_level: 0,
-            _reserved: [0u8; 64],
+            risk_params: vec![RiskParams::default()],
+            total_value_locked: 1_000_000,
+            compound_interval_seconds: 0,
+            performance_fee_bps: 0,
+            keeper_bounty_bps: 0,
+            withdraw_fee: FeeSchedule::default(),
+            transfer_fee: FeeSchedule::default(),
+            emergency_withdraw_fee: FeeSchedule::default(),
+            custody_fee_bps: 0,
+            custody_fee_index: 0,
+            custody_fee_index_updated_at: 0,
+            custody_fee_exempt_tiers: 0,
+            lock_lease_grace_seconds: 0,
+            free_margin_withdrawals: false,
+            policy_change_delay_floor_seconds: 0,
+            layout_version: 0,
+            _reserved: [0u8; 63],
         };
 
         let data = va.try_to_vec().unwrap();
         let back = VaultAuthority::try_from_slice(&data).unwrap();
         assert_eq!(back.governance, governance);
-        assert_eq!(back.authorized_programs.len(), programs.len());
+        assert_eq!(back.yield_whitelist.len(), programs.len());
         assert_eq!(back.bump, 200);
         assert!(!back.freeze);
+        assert_eq!(back.risk_params.len(), 1);
+    }
+
+    #[test]
+    fn risk_params_resolve_override_before_default_level() {
+        let conservative = RiskParams {
+            max_locked_bps: 5_000,
+            max_yield_bps: 2_000,
+            min_free_buffer_bps: 1_000,
+        };
+        let aggressive = RiskParams {
+            max_locked_bps: 9_000,
+            max_yield_bps: 8_000,
+            min_free_buffer_bps: 0,
+        };
+        let va = VaultAuthority {
+            governance: Pubkey::new_unique(),
+            bump: 255,
+            freeze: false,
+            cpi_enforced: false,
+            yield_whitelist: vec![],
+            risk_level: 0,
+            risk_params: vec![conservative, aggressive],
+            total_value_locked: 0,
+            compound_interval_seconds: 0,
+            performance_fee_bps: 0,
+            keeper_bounty_bps: 0,
+            withdraw_fee: FeeSchedule::default(),
+            transfer_fee: FeeSchedule::default(),
+            emergency_withdraw_fee: FeeSchedule::default(),
+            custody_fee_bps: 0,
+            custody_fee_index: 0,
+            custody_fee_index_updated_at: 0,
+            custody_fee_exempt_tiers: 0,
+            lock_lease_grace_seconds: 0,
+            free_margin_withdrawals: false,
+            policy_change_delay_floor_seconds: 0,
+            layout_version: 0,
+            _reserved: [0u8; 63],
+        };
+        assert!(va.risk_params_for(None) == Some(conservative));
+        assert!(va.risk_params_for(Some(1)) == Some(aggressive));
+        assert!(va.risk_params_for(Some(5)).is_none());
+    }
+
+    #[test]
+    fn risk_params_enforce_lock_and_yield_limits() {
+        let params = RiskParams {
+            max_locked_bps: 5_000,
+            max_yield_bps: 3_000,
+            min_free_buffer_bps: 1_000,
+        };
+        // 50% locked, 50% free: at the lock cap
+        assert!(params.check_lock(1_000, 500, 500).is_ok());
+        assert!(params.check_lock(1_000, 501, 499).is_err());
+        // 30% deployed but only 5% left free breaches the buffer
+        assert!(params.check_yield(1_000, 300, 700).is_ok());
+        assert!(params.check_yield(1_000, 300, 50).is_err());
+        assert!(params.check_yield(1_000, 301, 699).is_err());
+        assert!(RiskParams {
+            max_locked_bps: 10_001,
+            ..params
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn compound_fee_split_pays_keeper_first_and_caps_to_available() {
+        use crate::instructions::crank_compound::compound_fee_split;
+        // 10% fee and 1% bounty on 10_000 of claimed rewards
+        assert_eq!(compound_fee_split(10_000, 1_000, 100, 50_000), (100, 1_000));
+        // Only 500 available: the keeper is paid in full, the fee takes the rest
+        assert_eq!(compound_fee_split(10_000, 1_000, 100, 500), (100, 400));
+        assert_eq!(compound_fee_split(10_000, 1_000, 100, 0), (0, 0));
+        // Nothing claimed (a pure accrued-to-principal compound): nothing charged
+        assert_eq!(compound_fee_split(0, 1_000, 100, 50_000), (0, 0));
+        // Never more than the claimed rewards, whatever the rates
+        assert_eq!(compound_fee_split(100, 10_000, 10_000, 50_000), (100, 0));
+    }
+
+    #[test]
+    fn fee_schedule_applies_minimum_and_caps_to_amount() {
+        let schedule = FeeSchedule { bps: 50, min: 100 };
+        // 0.5% of 1_000_000 is above the minimum
+        assert_eq!(schedule.fee_for(1_000_000), 5_000);
+        // 0.5% of 10_000 is 50, raised to the minimum
+        assert_eq!(schedule.fee_for(10_000), 100);
+        // Never more than the amount itself
+        assert_eq!(schedule.fee_for(60), 60);
+        assert_eq!(FeeSchedule::default().fee_for(1_000_000), 0);
+        assert!(FeeSchedule {
+            bps: MAX_FEE_BPS + 1,
+            min: 0
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn custody_fee_accrues_on_index_and_skips_exempt_tiers() {
+        let mut va = VaultAuthority {
+            governance: Pubkey::new_unique(),
+            bump: 255,
+            freeze: false,
+            cpi_enforced: false,
+            yield_whitelist: vec![],
+            risk_level: 0,
+            risk_params: vec![],
+            total_value_locked: 0,
+            compound_interval_seconds: 0,
+            performance_fee_bps: 0,
+            keeper_bounty_bps: 0,
+            withdraw_fee: FeeSchedule::default(),
+            transfer_fee: FeeSchedule::default(),
+            emergency_withdraw_fee: FeeSchedule::default(),
+            custody_fee_bps: 100,
+            custody_fee_index: 0,
+            custody_fee_index_updated_at: 0,
+            custody_fee_exempt_tiers: 0b10,
+            lock_lease_grace_seconds: 0,
+            free_margin_withdrawals: false,
+            policy_change_delay_floor_seconds: 0,
+            layout_version: 0,
+            _reserved: [0u8; 63],
+        };
+        let mut vault = sample_vault();
+        vault.total_balance = 1_000_000;
+        vault.available_balance = 1_000_000;
+        vault.locked_balance = 0;
+
+        // First accrual only snapshots the index
+        assert_eq!(vault.accrue_custody_fee(&va, 1_000).unwrap(), 0);
+        // One year at 1% of 1_000_000
+        let year_later = 1_000 + SECONDS_PER_YEAR;
+        assert_eq!(vault.accrue_custody_fee(&va, year_later).unwrap(), 10_000);
+        assert_eq!(vault.take_custody_fee_owed(), 10_000);
+        assert_eq!(vault.total_balance, 990_000);
+        assert_eq!(vault.custody_fee_owed, 0);
+
+        // Tier 1 is exempt
+        vault.risk_tier_override = Some(1);
+        assert_eq!(
+            vault
+                .accrue_custody_fee(&va, year_later + SECONDS_PER_YEAR)
+                .unwrap(),
+            0
+        );
+        va.custody_fee_exempt_tiers = 0;
+        assert!(!va.is_custody_fee_exempt(Some(1)));
+    }
+
+    #[test]
+    fn integrator_locks_respect_opt_in_and_cap() {
+        let program = Pubkey::new_unique();
+        let mut vault = sample_vault();
+        assert!(vault.record_integrator_lock(program, 10).is_err());
+
+        vault.integrators.push(IntegratorApproval {
+            program,
+            max_locked: Some(100),
+            locked: 0,
+            lease_expires_at: 0,
+            attestation_nonce: 0,
+        });
+        assert!(vault.require_integrator(program).is_ok());
+        assert!(vault.record_integrator_lock(program, 60).is_ok());
+        assert!(vault.record_integrator_lock(program, 41).is_err());
+        assert!(vault.record_integrator_lock(program, 40).is_ok());
+        assert_eq!(vault.integrators[0].locked, 100);
+
+        assert!(vault.record_integrator_unlock(program, 150).is_err());
+        assert!(vault.record_integrator_unlock(program, 100).is_ok());
+        assert_eq!(vault.integrators[0].locked, 0);
+    }
+
+    #[test]
+    fn integrator_cannot_unlock_another_integrators_lock() {
+        let (a, b, stranger) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut vault = sample_vault();
+        for program in [a, b] {
+            vault.integrators.push(IntegratorApproval {
+                program,
+                max_locked: None,
+                locked: 0,
+                lease_expires_at: 0,
+                attestation_nonce: 0,
+            });
+        }
+        assert!(vault.record_integrator_lock(a, 10).is_ok());
+        assert!(vault.record_integrator_lock(b, 50).is_ok());
+
+        assert!(vault.record_integrator_unlock(a, 11).is_err());
+        assert!(vault.record_integrator_unlock(stranger, 1).is_err());
+        assert_eq!(vault.integrators[0].locked, 10);
+        assert_eq!(vault.integrators[1].locked, 50);
+
+        assert!(vault.record_integrator_unlock(a, 10).is_ok());
+        assert_eq!(vault.integrators[1].locked, 50);
+    }
+
+    #[test]
+    fn integrator_registry_checks_capability_status_and_exposure() {
+        use crate::state::integrator_registry::{CAP_LOCK, CAP_TRANSFER, CAP_UNLOCK};
+        let program = Pubkey::new_unique();
+        let mut integrator = IntegratorRegistry {
+            program,
+            capabilities: CAP_LOCK | CAP_UNLOCK,
+            enabled: true,
+            max_total_locked: Some(1_000),
+            total_locked: 0,
+            name: "perps".to_string(),
+            version: 1,
+            registered_at: 0,
+            updated_at: 0,
+            deprecated_at: 0,
+            grace_period_ends_at: 0,
+            risk_signer: None,
+            bump: 255,
+            _reserved: [0u8; 32],
+        };
+
+        assert!(integrator.require_capability(program, CAP_LOCK).is_ok());
+        assert!(integrator
+            .require_capability(program, CAP_TRANSFER)
+            .is_err());
+        assert!(integrator
+            .require_capability(Pubkey::new_unique(), CAP_LOCK)
+            .is_err());
+
+        assert!(integrator.record_lock(800).is_ok());
+        assert!(integrator.record_lock(201).is_err());
+        integrator.record_unlock(300);
+        assert_eq!(integrator.total_locked, 500);
+
+        integrator.enabled = false;
+        assert!(integrator.require_capability(program, CAP_UNLOCK).is_err());
+        assert!(integrator.try_to_vec().unwrap().len() + 8 <= IntegratorRegistry::LEN);
+    }
+
+    #[test]
+    fn deprecated_integrator_only_unlocks_and_strands_locks_after_grace() {
+        use crate::state::integrator_registry::{CAP_ALL, CAP_LOCK, CAP_UNLOCK};
+        let program = Pubkey::new_unique();
+        let mut integrator = IntegratorRegistry {
+            program,
+            capabilities: CAP_ALL,
+            enabled: true,
+            max_total_locked: None,
+            total_locked: 300,
+            name: String::new(),
+            version: 1,
+            registered_at: 0,
+            updated_at: 0,
+            deprecated_at: 100,
+            grace_period_ends_at: 1_000,
+            risk_signer: None,
+            bump: 255,
+            _reserved: [0u8; 32],
+        };
+        assert!(integrator.require_capability(program, CAP_LOCK).is_err());
+        assert!(integrator.require_capability(program, CAP_UNLOCK).is_ok());
+        assert!(!integrator.is_wound_down(999));
+        assert!(integrator.is_wound_down(1_000));
+
+        let mut vault = sample_vault();
+        vault.available_balance = 700;
+        vault.locked_balance = 300;
+        vault.total_balance = 1_000;
+        vault.integrators.push(IntegratorApproval {
+            program,
+            max_locked: None,
+            locked: 300,
+            lease_expires_at: 0,
+            attestation_nonce: 0,
+        });
+        let released = vault.release_stranded_lock(program).unwrap();
+        integrator.record_unlock(released);
+        assert_eq!(released, 300);
+        assert_eq!(vault.locked_balance, 0);
+        assert_eq!(vault.available_balance, 1_000);
+        assert!(vault.integrators.is_empty());
+        assert_eq!(integrator.total_locked, 0);
+        assert!(vault.release_stranded_lock(program).is_err());
+    }
+
+    #[test]
+    fn lock_lease_renews_and_clears_when_released() {
+        let program = Pubkey::new_unique();
+        let mut vault = sample_vault();
+        vault.integrators.push(IntegratorApproval {
+            program,
+            max_locked: None,
+            locked: 45,
+            lease_expires_at: 0,
+            attestation_nonce: 0,
+        });
+
+        assert!(vault.renew_integrator_lease(program, 1_000, 0).is_err());
+        assert!(vault
+            .renew_integrator_lease(program, 1_000, MAX_LOCK_LEASE_SECONDS + 1)
+            .is_err());
+        assert!(vault
+            .renew_integrator_lease(Pubkey::new_unique(), 1_000, 60)
+            .is_err());
+        assert_eq!(
+            vault.renew_integrator_lease(program, 1_000, 60).unwrap(),
+            1_060
+        );
+
+        let released = vault.release_integrator_lock(program).unwrap();
+        assert_eq!(released, 45);
+        assert_eq!(vault.locked_balance, 0);
+        assert_eq!(vault.available_balance, 123);
+        assert!(
+            vault.integrators[0]
+                == IntegratorApproval {
+                    program,
+                    max_locked: None,
+                    locked: 0,
+                    lease_expires_at: 0,
+                    attestation_nonce: 0,
+                }
+        );
+    }
+
+    #[test]
+    fn ed25519_parser_reads_inline_attestations_only() {
+        use crate::attestation::{parse_ed25519_instruction, RiskAttestation};
+
+        let signer = Pubkey::new_unique();
+        let attestation = RiskAttestation {
+            integrator: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            open_positions: 2,
+            required_margin: 500,
+            expires_at: 1_300,
+            nonce: 7,
+        };
+        let message = attestation.message();
+        // Header, one offsets record, then signature, public key and message
+        let signature_offset = 2 + 14;
+        let pubkey_offset = signature_offset + 64;
+        let message_offset = pubkey_offset + 32;
+        let build = |message_ix: u16| {
+            let mut data = vec![1u8, 0];
+            for field in [
+                signature_offset as u16,
+                u16::MAX,
+                pubkey_offset as u16,
+                u16::MAX,
+                message_offset as u16,
+                message.len() as u16,
+                message_ix,
+            ] {
+                data.extend_from_slice(&field.to_le_bytes());
+            }
+            data.extend_from_slice(&[0u8; 64]);
+            data.extend_from_slice(signer.as_ref());
+            data.extend_from_slice(&message);
+            data
+        };
+
+        let entries = parse_ed25519_instruction(&build(u16::MAX)).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, signer);
+        let parsed = RiskAttestation::from_message(&entries[0].1).unwrap();
+        assert_eq!(parsed.nonce, 7);
+        assert_eq!(parsed.required_margin, 500);
+
+        assert!(parse_ed25519_instruction(&build(0)).is_err());
+        assert!(parse_ed25519_instruction(&build(u16::MAX)[..40]).is_err());
+    }
+
+    #[test]
+    fn co_signer_policy_changes_are_delayed_and_approvals_single_use() {
+        use crate::attestation::CoSignerApproval;
+
+        let mut vault = sample_vault();
+        let vault_key = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let policy = CoSignerPolicy {
+            signer: Pubkey::new_unique(),
+            threshold: 1_000,
+        };
+
+        // A first policy applies at once
+        assert_eq!(
+            vault.propose_co_signer_policy(Some(policy), 100).unwrap(),
+            100
+        );
+        assert!(vault.co_signer == Some(policy));
+        assert!(vault.co_signer_for(1_000).is_none());
+        assert_eq!(vault.co_signer_for(1_001), Some(policy.signer));
+
+        // Removing it waits out the delay
+        let effective_at = vault.propose_co_signer_policy(None, 200).unwrap();
+        assert_eq!(effective_at, 200 + CO_SIGNER_POLICY_DELAY_SECONDS);
+        assert!(vault
+            .apply_pending_co_signer_policy(effective_at - 1)
+            .is_err());
+        assert!(vault.co_signer == Some(policy));
+
+        let approval = CoSignerApproval {
+            vault: vault_key,
+            amount: 5_000,
+            recipient,
+            expires_at: 400,
+            nonce: 1,
+        };
+        assert!(CoSignerApproval::from_message(&approval.message()) == Some(approval));
+        assert!(vault
+            .accept_co_signer_approval(&approval, 4_999, recipient, 300)
+            .is_err());
+        assert!(vault
+            .accept_co_signer_approval(&approval, 5_000, Pubkey::new_unique(), 300)
+            .is_err());
+        assert!(vault
+            .accept_co_signer_approval(&approval, 5_000, recipient, 401)
+            .is_err());
+        assert!(vault
+            .accept_co_signer_approval(&approval, 5_000, recipient, 300)
+            .is_ok());
+        assert!(vault
+            .accept_co_signer_approval(&approval, 5_000, recipient, 300)
+            .is_err());
+
+        assert!(vault
+            .apply_pending_co_signer_policy(effective_at)
+            .unwrap()
+            .is_none());
+        assert!(vault.pending_co_signer.is_none());
+        assert!(vault.co_signer_for(u64::MAX).is_none());
+    }
+
+    #[test]
+    fn policy_relaxations_queue_and_tightenings_do_not() {
+        let mut vault = sample_vault();
+        vault.min_withdraw_delay_seconds = 3_600;
+        vault.rate_window_seconds = 86_400;
+        vault.rate_limit_amount = 1_000;
+        let delegate = Pubkey::new_unique();
+        vault.delegates.push(delegate);
+
+        let shorter = PolicyChange::MinWithdrawDelay { seconds: 60 };
+        assert!(vault.is_policy_relaxation(&shorter));
+        assert!(!vault.is_policy_relaxation(&PolicyChange::MinWithdrawDelay { seconds: 7_200 }));
+        assert!(vault.is_policy_relaxation(&PolicyChange::RateLimit {
+            window_seconds: 86_400,
+            max_amount: 2_000,
+        }));
+        assert!(vault.is_policy_relaxation(&PolicyChange::RateLimit {
+            window_seconds: 0,
+            max_amount: 0,
+        }));
+        assert!(!vault.is_policy_relaxation(&PolicyChange::RateLimit {
+            window_seconds: 86_400,
+            max_amount: 500,
+        }));
+        assert!(vault.is_policy_relaxation(&PolicyChange::AddWhitelist {
+            address: Pubkey::new_unique(),
+        }));
+
+        // Single-owner mode: a 2-of-2 with the owner and one new key tightens,
+        // while a 1-of-1 on a new key hands it the vault
+        let hardware_key = Pubkey::new_unique();
+        assert!(!vault.is_policy_relaxation(&PolicyChange::Multisig {
+            signers: vec![vault.owner, hardware_key],
+            threshold: 2,
+        }));
+        assert!(vault.is_policy_relaxation(&PolicyChange::Multisig {
+            signers: vec![hardware_key],
+            threshold: 1,
+        }));
+        vault.multisig_signers = vec![vault.owner, hardware_key, delegate];
+        vault.multisig_threshold = 2;
+        assert!(vault.is_policy_relaxation(&PolicyChange::Multisig {
+            signers: vec![],
+            threshold: 0,
+        }));
+        assert!(!vault.is_policy_relaxation(&PolicyChange::Multisig {
+            signers: vec![vault.owner, hardware_key],
+            threshold: 2,
+        }));
+
+        // The delay is the longer of the vault's own delay and the governance floor
+        assert_eq!(vault.policy_change_delay(600), 3_600);
+        assert_eq!(vault.policy_change_delay(10_000), 10_000);
+
+        assert_eq!(
+            vault.queue_policy_change(shorter, 100, 3_600).unwrap(),
+            3_700
+        );
+        // A second submission for the same setting replaces the first
+        let shortest = PolicyChange::MinWithdrawDelay { seconds: 0 };
+        assert_eq!(
+            vault
+                .queue_policy_change(shortest.clone(), 200, 3_600)
+                .unwrap(),
+            3_800
+        );
+        assert_eq!(vault.pending_policy_changes.len(), 1);
+        assert!(vault.take_matured_policy_changes(3_799).is_empty());
+        assert!(vault.take_matured_policy_changes(3_800) == vec![shortest]);
+        assert!(vault.pending_policy_changes.is_empty());
+    }
+
+    #[test]
+    fn panic_lock_needs_owner_and_cooldown_to_lift() {
+        let mut vault = sample_vault();
+        let delegate = Pubkey::new_unique();
+        let signer = Pubkey::new_unique();
+        vault.delegates.push(delegate);
+        vault.multisig_signers.push(signer);
+
+        assert!(vault.can_panic_lock(&vault.owner));
+        assert!(vault.can_panic_lock(&delegate));
+        assert!(vault.can_panic_lock(&signer));
+        assert!(!vault.can_panic_lock(&Pubkey::new_unique()));
+
+        // Delegates may act for the owner elsewhere but never unlock
+        assert!(vault.check_authority(delegate, &[]).is_ok());
+        assert!(vault.check_owner_authority(delegate, &[]).is_err());
+        assert!(vault.check_owner_authority(vault.owner, &[]).is_ok());
+
+        assert!(vault.request_panic_unlock(0).is_err());
+        vault.panic_lock(100);
+        assert!(vault.is_panic_locked());
+        assert!(vault.complete_panic_unlock(200).is_err());
+
+        let unlocks_at = vault.request_panic_unlock(200).unwrap();
+        assert_eq!(unlocks_at, 200 + PANIC_UNLOCK_COOLDOWN_SECONDS);
+        assert!(vault.complete_panic_unlock(unlocks_at - 1).is_err());
+
+        // Locking again cancels the pending unlock
+        vault.panic_lock(300);
+        assert_eq!(vault.panic_locked_at, 100);
+        assert!(vault.complete_panic_unlock(unlocks_at).is_err());
+
+        let unlocks_at = vault.request_panic_unlock(400).unwrap();
+        vault.complete_panic_unlock(unlocks_at).unwrap();
+        assert!(!vault.is_panic_locked());
+        assert_eq!(vault.panic_unlock_requested_at, 0);
+    }
+
+    #[test]
+    fn guardians_rotate_owner_after_delay_and_reset_signing_setup() {
+        let mut vault = sample_vault();
+        let original_owner = vault.owner;
+        let (g1, g2, g3) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        assert!(vault.start_recovery(g1, 0).is_err());
+        assert!(vault.propose_guardians(vec![g1, g1], 1, 0).is_err());
+        assert!(vault
+            .propose_guardians(vec![g1, original_owner], 1, 0)
+            .is_err());
+        assert!(vault.propose_guardians(vec![g1, g2], 3, 0).is_err());
+        assert!(vault.propose_guardians(vec![g1, g2], 0, 0).is_err());
+
+        let effective_at = vault.propose_guardians(vec![g1, g2, g3], 2, 0).unwrap();
+        assert_eq!(effective_at, GUARDIAN_CHANGE_DELAY_SECONDS);
+        assert!(vault.apply_pending_guardians(effective_at - 1).is_err());
+        assert!(vault.pending_guardians.is_some());
+        vault.apply_pending_guardians(effective_at).unwrap();
+        assert_eq!(vault.guardian_threshold, 2);
+
+        // One guardian is not enough
+        assert!(vault.check_guardians(g1, &[]).is_err());
+
+        vault.delegates.push(Pubkey::new_unique());
+        vault.multisig_signers = vec![original_owner];
+        vault.multisig_threshold = 1;
+        let new_owner = Pubkey::new_unique();
+        assert!(vault.start_recovery(original_owner, 100).is_err());
+        let executable_at = vault.start_recovery(new_owner, 100).unwrap();
+        assert_eq!(executable_at, 100 + RECOVERY_DELAY_SECONDS);
+        assert!(vault.start_recovery(new_owner, 100).is_err());
+        assert!(vault.complete_recovery(executable_at - 1).is_err());
+
+        assert_eq!(
+            vault.complete_recovery(executable_at).unwrap(),
+            original_owner
+        );
+        assert_eq!(vault.owner, new_owner);
+        // The PDA seed does not move with the owner
+        assert_eq!(vault.original_owner, original_owner);
+        assert!(vault.delegates.is_empty());
+        assert_eq!(vault.multisig_threshold, 0);
+        assert!(vault.multisig_signers.is_empty());
+        assert!(vault.pending_recovery.is_none());
+        assert_eq!(vault.guardians.len(), 3);
+    }
+
+    #[test]
+    fn beneficiary_inherits_only_after_owner_inactivity() {
+        let mut vault = sample_vault();
+        let owner = vault.owner;
+        let beneficiary = Pubkey::new_unique();
+
+        assert!(vault.claim_inheritance(i64::MAX).is_err());
+        assert!(vault
+            .set_beneficiary(Some(owner), MIN_INACTIVITY_PERIOD_SECONDS)
+            .is_err());
+        assert!(vault
+            .set_beneficiary(Some(beneficiary), MIN_INACTIVITY_PERIOD_SECONDS - 1)
+            .is_err());
+        vault
+            .set_beneficiary(Some(beneficiary), MIN_INACTIVITY_PERIOD_SECONDS)
+            .unwrap();
+
+        // Owner activity restarts the clock; anyone else's does not
+        vault.record_owner_activity(owner, &[], 1_000);
+        vault.record_owner_activity(beneficiary, &[], 5_000);
+        let claimable_at = 1_000 + MIN_INACTIVITY_PERIOD_SECONDS;
+        assert!(!vault.can_inherit(&beneficiary, claimable_at - 1));
+        assert!(vault.claim_inheritance(claimable_at - 1).is_err());
+        assert!(vault.can_inherit(&beneficiary, claimable_at));
+        assert!(!vault.can_inherit(&Pubkey::new_unique(), claimable_at));
+
+        // On a multisig vault the signer threshold counts as owner activity
+        let signer = Pubkey::new_unique();
+        vault.multisig_signers = vec![signer];
+        vault.multisig_threshold = 1;
+        vault.record_owner_activity(signer, &[], 2_000);
+        assert!(!vault.can_inherit(&beneficiary, claimable_at));
+        let claimable_at = 2_000 + MIN_INACTIVITY_PERIOD_SECONDS;
+        assert!(vault.can_inherit(&beneficiary, claimable_at));
+
+        vault.delegates.push(Pubkey::new_unique());
+        assert_eq!(vault.claim_inheritance(claimable_at).unwrap(), owner);
+        assert_eq!(vault.owner, beneficiary);
+        assert_eq!(vault.original_owner, owner);
+        assert!(vault.delegates.is_empty());
+        assert!(vault.beneficiary.is_none());
+        assert_eq!(vault.last_owner_activity_at, claimable_at);
+
+        // Removing the beneficiary clears the period
+        vault
+            .set_beneficiary(Some(owner), MIN_INACTIVITY_PERIOD_SECONDS)
+            .unwrap();
+        vault.set_beneficiary(None, 0).unwrap();
+        assert_eq!(vault.inactivity_period_seconds, 0);
+        assert!(!vault.can_inherit(&owner, i64::MAX));
+    }
+
+    #[test]
+    fn version_one_accounts_upgrade_to_current_layout() {
+        use crate::constants::ACCOUNT_LAYOUT_VERSION;
+        use crate::state::{LegacyCollateralVault, LegacyVaultAuthority};
+        use crate::types::TimelockEntry;
+        use anchor_lang::Discriminator;
+
+        let owner = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let legacy = LegacyCollateralVault {
+            owner,
+            token_account: Pubkey::new_unique(),
+            usdt_mint: Pubkey::new_unique(),
+            total_balance: 700,
+            locked_balance: 200,
+            available_balance: 400,
+            total_deposited: 900,
+            total_withdrawn: 200,
+            yield_deposited_balance: 0,
+            yield_accrued_balance: 0,
+            last_compounded_at: 0,
+            active_yield_program: Pubkey::default(),
+            created_at: 10,
+            bump: 254,
+            multisig_threshold: 0,
+            multisig_signers: vec![],
+            delegates: vec![delegate],
+            timelocks: vec![TimelockEntry {
+                amount: 100,
+                unlock_time: 50,
+            }],
+            min_withdraw_delay_seconds: 60,
+            pending_withdrawals: vec![],
+            withdraw_whitelist: vec![],
+            rate_window_seconds: 0,
+            rate_limit_amount: 0,
+            last_withdrawal_window_start: 0,
+            withdrawn_in_window: 0,
+            _reserved: [0u8; 64],
+        };
+        let mut data = CollateralVault::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.resize(LegacyCollateralVault::LEN, 0);
+
+        let vault = LegacyCollateralVault::try_from_data(&data)
+            .unwrap()
+            .upgrade(77);
+        assert_eq!(vault.owner, owner);
+        assert_eq!(vault.original_owner, owner);
+        assert!(vault.delegates == vec![delegate]);
+        assert_eq!(vault.min_withdraw_delay_seconds, 60);
+        assert_eq!(vault.last_owner_activity_at, 77);
+        assert_eq!(vault.layout_version, ACCOUNT_LAYOUT_VERSION);
+        assert!(vault.check_balance_invariant().is_ok());
+
+        // The current layout round-trips and is not mistaken for version 1
+        let mut current = vec![0u8; CollateralVault::LEN];
+        vault.try_serialize(&mut &mut current[..]).unwrap();
+        let decoded = CollateralVault::try_deserialize(&mut &current[..]).unwrap();
+        assert_eq!(decoded.total_balance, 700);
+        assert!(LegacyCollateralVault::try_from_data(&current).is_none());
+
+        let governance = Pubkey::new_unique();
+        let legacy = LegacyVaultAuthority {
+            governance,
+            authorized_programs: vec![Pubkey::new_unique()],
+            bump: 255,
+            freeze: false,
+            cpi_enforced: true,
+            yield_whitelist: vec![Pubkey::new_unique()],
+            risk_level: 2,
+            _reserved: [0u8; 64],
+        };
+        let mut data = VaultAuthority::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.resize(LegacyVaultAuthority::LEN, 0);
+
+        let va = LegacyVaultAuthority::try_from_data(&data)
+            .unwrap()
+            .upgrade();
+        assert_eq!(va.governance, governance);
+        assert!(va.cpi_enforced);
+        assert_eq!(va.yield_whitelist.len(), 1);
+        assert_eq!(va.risk_level, 2);
+        let mut current = vec![0u8; VaultAuthority::LEN];
+        va.try_serialize(&mut &mut current[..]).unwrap();
+        assert!(VaultAuthority::try_deserialize(&mut &current[..]).is_ok());
+        assert!(LegacyVaultAuthority::try_from_data(&current).is_none());
+    }
+
+    #[test]
+    fn version_one_locks_are_released_only_through_governance() {
+        use crate::state::LegacyCollateralVault;
+
+        let program = Pubkey::new_unique();
+        let mut vault = LegacyCollateralVault {
+            owner: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            usdt_mint: Pubkey::new_unique(),
+            total_balance: 600,
+            locked_balance: 200,
+            available_balance: 400,
+            total_deposited: 600,
+            total_withdrawn: 0,
+            yield_deposited_balance: 0,
+            yield_accrued_balance: 0,
+            last_compounded_at: 0,
+            active_yield_program: Pubkey::default(),
+            created_at: 10,
+            bump: 254,
+            multisig_threshold: 0,
+            multisig_signers: vec![],
+            delegates: vec![],
+            timelocks: vec![],
+            min_withdraw_delay_seconds: 0,
+            pending_withdrawals: vec![],
+            withdraw_whitelist: vec![],
+            rate_window_seconds: 0,
+            rate_limit_amount: 0,
+            last_withdrawal_window_start: 0,
+            withdrawn_in_window: 0,
+            _reserved: [0u8; 64],
+        }
+        .upgrade(0);
+        // The old position manager is approved again after the upgrade and locks more
+        vault.integrators.push(IntegratorApproval {
+            program,
+            max_locked: None,
+            locked: 0,
+            lease_expires_at: 0,
+            attestation_nonce: 0,
+        });
+        assert!(vault.record_integrator_lock(program, 50).is_ok());
+        vault.locked_balance += 50;
+        vault.available_balance -= 50;
+        assert_eq!(vault.unattributed_locked(), 200);
+
+        // It can only unlock what it locked since the upgrade
+        assert!(vault.record_integrator_unlock(program, 51).is_err());
+
+        assert_eq!(vault.release_unattributed_lock().unwrap(), 200);
+        assert_eq!(vault.locked_balance, 50);
+        assert_eq!(vault.available_balance, 550);
+        assert!(vault.check_balance_invariant().is_ok());
+        assert!(vault.release_unattributed_lock().is_err());
+        assert_eq!(vault.integrators[0].locked, 50);
     }
 }
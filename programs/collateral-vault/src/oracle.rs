@@ -0,0 +1,131 @@
+//! Oracle adapter for pricing non-stable collateral.
+//!
+//! Price accounts use a fixed, Pyth-style little-endian layout starting at
+//! offset 0 of the account data (no Anchor discriminator):
+//!
+//! | Offset | Size | Field          | Notes                                   |
+//! |--------|------|----------------|-----------------------------------------|
+//! | 0      | 4    | `magic`        | [`PRICE_FEED_MAGIC`]                    |
+//! | 4      | 4    | `version`      | [`PRICE_FEED_VERSION`]                  |
+//! | 8      | 4    | `expo`         | `i32`, price = `price * 10^expo` USD    |
+//! | 12     | 4    | padding        | zero                                    |
+//! | 16     | 8    | `price`        | `i64`, must be positive                 |
+//! | 24     | 8    | `conf`         | `u64`, confidence interval, same expo   |
+//! | 32     | 8    | `publish_time` | `i64`, unix timestamp                   |
+//!
+//! Any program may publish feeds in this layout; governance pins the exact
+//! price account per mint in [`crate::state::CollateralConfig`].
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{BPS_DENOMINATOR, MAX_ORACLE_CLOCK_SKEW_SECONDS, USD_VALUE_DECIMALS};
+use crate::error::ErrorCode;
+
+pub const PRICE_FEED_MAGIC: u32 = 0x5052_4346; // "PRCF"
+pub const PRICE_FEED_VERSION: u32 = 1;
+pub const PRICE_FEED_LEN: usize = 40;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct PriceFeed {
+    pub expo: i32,
+    pub price: i64,
+    pub conf: u64,
+    pub publish_time: i64,
+}
+
+impl PriceFeed {
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= PRICE_FEED_LEN,
+            ErrorCode::InvalidOracleAccount
+        );
+        let magic = u32::from_le_bytes(data[0..4].try_into().unwrap());
+        let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+        require!(
+            magic == PRICE_FEED_MAGIC && version == PRICE_FEED_VERSION,
+            ErrorCode::InvalidOracleAccount
+        );
+        Ok(Self {
+            expo: i32::from_le_bytes(data[8..12].try_into().unwrap()),
+            price: i64::from_le_bytes(data[16..24].try_into().unwrap()),
+            conf: u64::from_le_bytes(data[24..32].try_into().unwrap()),
+            publish_time: i64::from_le_bytes(data[32..40].try_into().unwrap()),
+        })
+    }
+
+    pub fn pack_into(&self, data: &mut [u8]) -> Result<()> {
+        require!(
+            data.len() >= PRICE_FEED_LEN,
+            ErrorCode::InvalidOracleAccount
+        );
+        data[0..4].copy_from_slice(&PRICE_FEED_MAGIC.to_le_bytes());
+        data[4..8].copy_from_slice(&PRICE_FEED_VERSION.to_le_bytes());
+        data[8..12].copy_from_slice(&self.expo.to_le_bytes());
+        data[12..16].copy_from_slice(&[0u8; 4]);
+        data[16..24].copy_from_slice(&self.price.to_le_bytes());
+        data[24..32].copy_from_slice(&self.conf.to_le_bytes());
+        data[32..40].copy_from_slice(&self.publish_time.to_le_bytes());
+        Ok(())
+    }
+
+    /// Reject prices that are non-positive, older than `max_staleness_seconds`,
+    /// published more than [`MAX_ORACLE_CLOCK_SKEW_SECONDS`] in the future, or
+    /// whose confidence interval exceeds `max_confidence_bps` of the price.
+    pub fn validate(
+        &self,
+        now: i64,
+        max_staleness_seconds: i64,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        require!(self.price > 0, ErrorCode::InvalidOracleAccount);
+        require!(
+            self.publish_time <= now.saturating_add(MAX_ORACLE_CLOCK_SKEW_SECONDS),
+            ErrorCode::OraclePublishTimeInFuture
+        );
+        let age = now.saturating_sub(self.publish_time);
+        require!(age <= max_staleness_seconds, ErrorCode::OraclePriceStale);
+        let conf_scaled = (self.conf as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        let limit = (self.price as u128)
+            .checked_mul(max_confidence_bps as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(conf_scaled <= limit, ErrorCode::OracleConfidenceTooWide);
+        Ok(())
+    }
+
+    /// USD value of `amount` base units of a mint with `mint_decimals`,
+    /// expressed with [`USD_VALUE_DECIMALS`] decimals.
+    pub fn usd_value(&self, amount: u64, mint_decimals: u8) -> Result<u64> {
+        require!(self.price > 0, ErrorCode::InvalidOracleAccount);
+        let raw = (amount as u128)
+            .checked_mul(self.price as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        // value = amount * price * 10^(expo + USD_VALUE_DECIMALS - mint_decimals)
+        let scale = self.expo as i64 + USD_VALUE_DECIMALS as i64 - mint_decimals as i64;
+        let value = if scale >= 0 {
+            let factor = 10u128
+                .checked_pow(scale as u32)
+                .ok_or(ErrorCode::Overflow)?;
+            raw.checked_mul(factor).ok_or(ErrorCode::Overflow)?
+        } else {
+            match 10u128.checked_pow(scale.unsigned_abs() as u32) {
+                Some(divisor) => raw / divisor,
+                None => 0,
+            }
+        };
+        u64::try_from(value).map_err(|_| error!(ErrorCode::Overflow))
+    }
+}
+
+/// Apply a haircut in basis points to a USD value (rounds down).
+pub fn apply_haircut(value: u64, haircut_bps: u16) -> Result<u64> {
+    let kept = BPS_DENOMINATOR
+        .checked_sub(haircut_bps as u64)
+        .ok_or(ErrorCode::InvalidRiskParams)?;
+    let adjusted = (value as u128)
+        .checked_mul(kept as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(adjusted).map_err(|_| error!(ErrorCode::Overflow))
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct CollateralConfig {
+    // Collateral mint this config applies to
+    pub mint: Pubkey, // 32
+    // Price account (see crate::oracle layout) used to value this mint
+    pub price_feed: Pubkey, // 32
+
+    // Valuation haircut applied to the oracle USD value (bps)
+    pub haircut_bps: u16, // 2
+    // Max age of the oracle price before it is rejected
+    pub max_staleness_seconds: i64, // 8
+    // Max confidence interval as a share of price (bps)
+    pub max_confidence_bps: u16, // 2
+
+    pub bump: u8, // 1
+
+    pub _reserved: [u8; 32], // 32
+}
+
+impl CollateralConfig {
+    pub const LEN: usize = 8  // discriminator
+        + 32                  // mint
+        + 32                  // price_feed
+        + 2                   // haircut_bps
+        + 8                   // max_staleness_seconds
+        + 2                   // max_confidence_bps
+        + 1                   // bump
+        + 32; // reserved
+}
@@ -1,8 +1,14 @@
+use crate::attestation::CoSignerApproval;
 use crate::constants::{
-    MAX_DELEGATES, MAX_MULTISIG_SIGNERS, MAX_PENDING_WITHDRAWALS, MAX_TIMELOCKS,
-    MAX_WITHDRAW_WHITELIST,
+    GUARDIAN_CHANGE_DELAY_SECONDS, MAX_DELEGATES, MAX_GUARDIANS, MAX_MULTISIG_SIGNERS,
+    MAX_PENDING_POLICY_CHANGES, MAX_PENDING_WITHDRAWALS, MAX_TIMELOCKS, MAX_VAULT_INTEGRATORS,
+    MAX_WITHDRAW_WHITELIST, MIN_INACTIVITY_PERIOD_SECONDS, SECONDS_PER_YEAR,
+};
+use crate::state::VaultAuthority;
+use crate::types::{
+    CoSignerPolicy, IntegratorApproval, PendingCoSignerPolicy, PendingGuardianSet,
+    PendingPolicyChange, PendingRecovery, PendingWithdrawalEntry, PolicyChange, TimelockEntry,
 };
-use crate::types::{PendingWithdrawalEntry, TimelockEntry};
 use anchor_lang::prelude::*;
 
 #[account]
@@ -29,10 +35,23 @@ pub struct CollateralVault {
     pub yield_accrued_balance: u64,   // 8 - unclaimed rewards accounted to the vault
     pub last_compounded_at: i64,      // 8 - unix timestamp of last compound
     pub active_yield_program: Pubkey, // 32 - currently selected yield program id (0 if none)
+    pub last_harvested_at: i64,       // 8 - unix timestamp of last harvest_yield
+    pub yield_target_weight_bps: u16, // 2 - sum of target weights across this vault's yield positions
+    pub pending_redemption_balance: u64, // 8 - requested from strategies, not yet paid back
+    pub min_liquid_reserve_bps: u16, // 2 - share of total_balance yield_deposit must leave available
+    pub yield_pullback_pending: u64, // 8 - yield to pull back to restore the liquid reserve
+    pub auto_compound_opt_out: bool, // 1 - when true, keepers may not crank_compound this vault
+    pub strict_withdrawals: bool, // 1 - when true, withdraw requires no open positions even if free-margin mode is on
+    pub last_fee_accrual_at: i64, // 8 - unix timestamp custody fees were last accrued
+    pub custody_fee_index_snapshot: u64, // 8 - VaultAuthority custody fee index at last accrual
+    pub custody_fee_owed: u64,    // 8 - accrued custody fees not yet collected
 
     // Metadata
     pub created_at: i64, // 8 (unix timestamp)
     pub bump: u8,        // 1
+    // Owner at creation: the vault PDA is derived from it, so it stays fixed when
+    // guardians rotate `owner`
+    pub original_owner: Pubkey, // 32
 
     // Multisig config (threshold == 0 means disabled)
     pub multisig_threshold: u8, // 1
@@ -63,11 +82,696 @@ pub struct CollateralVault {
     pub last_withdrawal_window_start: i64, // 8 (unix ts of window start)
     pub withdrawn_in_window: u64,          // 8 (used amount in window)
 
+    // Governance-assigned risk tier; None falls back to VaultAuthority.risk_level
+    pub risk_tier_override: Option<u8>, // 1 + 1
+
+    // Integrator programs the owner allows to lock and transfer this vault's collateral
+    #[max_len(MAX_VAULT_INTEGRATORS)]
+    pub integrators: Vec<IntegratorApproval>, // 4 + K*IntegratorApproval::LEN
+
+    // Co-signer (2FA) for large withdrawals and timelocks; changes wait out a delay
+    pub co_signer: Option<CoSignerPolicy>, // 1 + 40
+    pub pending_co_signer: Option<PendingCoSignerPolicy>, // 1 + 49
+    pub co_signer_nonce: u64,              // 8 - last co-signer approval nonce accepted
+
+    // Security relaxations waiting out the policy change delay
+    #[max_len(MAX_PENDING_POLICY_CHANGES)]
+    pub pending_policy_changes: Vec<PendingPolicyChange>, // 4 + N*PendingPolicyChange::LEN
+
+    // Panic lock: while set, outflows and policy changes are refused
+    pub panic_locked_at: i64,           // 8 (0 when not locked)
+    pub panic_unlock_requested_at: i64, // 8 (0 when no unlock is pending)
+
+    // Social recovery: guardian_threshold distinct guardians may rotate `owner`
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>, // 4 + N*32
+    pub guardian_threshold: u8, // 1 (0 when no guardians)
+    pub pending_guardians: Option<PendingGuardianSet>, // 1 + PendingGuardianSet::LEN
+    pub pending_recovery: Option<PendingRecovery>, // 1 + PendingRecovery::LEN
+
+    // Inheritance: the beneficiary may take over once the owner has signed
+    // nothing for inactivity_period_seconds
+    pub beneficiary: Option<Pubkey>,    // 1 + 32
+    pub inactivity_period_seconds: i64, // 8 (0 when no beneficiary)
+    pub last_owner_activity_at: i64,    // 8 - unix timestamp of the owner's last signed action
+
+    // Account layout version (1 = original deployment, which had no such byte)
+    pub layout_version: u8, // 1
+
     // Reserved for future upgrades to avoid migrations
-    pub _reserved: [u8; 64], // 64
+    pub _reserved: [u8; 63], // 63
 }
 
 impl CollateralVault {
+    /// Sum of scheduled timelock amounts not yet released to available.
+    pub fn timelocked_balance(&self) -> Result<u64> {
+        self.timelocks.iter().try_fold(0u64, |acc, e| {
+            acc.checked_add(e.amount)
+                .ok_or_else(|| error!(crate::error::ErrorCode::Overflow))
+        })
+    }
+
+    /// Authorization: owner or delegate when `multisig_threshold == 0`, otherwise
+    /// `multisig_threshold` distinct multisig signers among `authority` and the
+    /// signer `remaining_accounts`.
+    pub fn check_authority(
+        &self,
+        authority: Pubkey,
+        remaining_accounts: &[AccountInfo],
+    ) -> Result<()> {
+        self.check_signers(authority, remaining_accounts, true)
+    }
+
+    /// As `check_authority`, but delegates do not count: the owner alone in
+    /// single-owner mode, otherwise the multisig threshold.
+    pub fn check_owner_authority(
+        &self,
+        authority: Pubkey,
+        remaining_accounts: &[AccountInfo],
+    ) -> Result<()> {
+        self.check_signers(authority, remaining_accounts, false)
+    }
+
+    fn check_signers(
+        &self,
+        authority: Pubkey,
+        remaining_accounts: &[AccountInfo],
+        allow_delegates: bool,
+    ) -> Result<()> {
+        let threshold = self.multisig_threshold;
+        if threshold == 0 {
+            require!(
+                authority == self.owner || (allow_delegates && self.delegates.contains(&authority)),
+                crate::error::ErrorCode::Unauthorized
+            );
+            return Ok(());
+        }
+        let allowed: &Vec<Pubkey> = &self.multisig_signers;
+        require!(!allowed.is_empty(), crate::error::ErrorCode::Unauthorized);
+        require!(
+            (threshold as usize) <= allowed.len(),
+            crate::error::ErrorCode::Unauthorized
+        );
+        let mut approved: u8 = 0;
+        let mut seen: std::collections::BTreeSet<Pubkey> = std::collections::BTreeSet::new();
+        if allowed.contains(&authority) {
+            approved = approved.saturating_add(1);
+            let _ = seen.insert(authority);
+        }
+        for ai in remaining_accounts.iter() {
+            if !ai.is_signer || seen.contains(&ai.key()) {
+                continue;
+            }
+            if allowed.contains(ai.key) {
+                approved = approved.saturating_add(1);
+                let _ = seen.insert(ai.key());
+                if approved >= threshold {
+                    break;
+                }
+            }
+        }
+        require!(approved >= threshold, crate::error::ErrorCode::Unauthorized);
+        Ok(())
+    }
+
+    /// True while an owner, delegate or multisig signer has the vault panic-locked.
+    pub fn is_panic_locked(&self) -> bool {
+        self.panic_locked_at != 0
+    }
+
+    /// Whether `key` may panic-lock the vault: the owner, a delegate or any
+    /// single multisig signer.
+    pub fn can_panic_lock(&self, key: &Pubkey) -> bool {
+        *key == self.owner || self.delegates.contains(key) || self.multisig_signers.contains(key)
+    }
+
+    /// Freeze outflows and policy changes. Locking an already locked vault keeps
+    /// the original lock time but cancels any pending unlock request.
+    pub fn panic_lock(&mut self, now: i64) {
+        if self.panic_locked_at == 0 {
+            self.panic_locked_at = now;
+        }
+        self.panic_unlock_requested_at = 0;
+    }
+
+    /// Start the unlock cooldown. Returns when the vault may be unlocked.
+    pub fn request_panic_unlock(&mut self, now: i64) -> Result<i64> {
+        require!(
+            self.is_panic_locked(),
+            crate::error::ErrorCode::VaultNotPanicLocked
+        );
+        self.panic_unlock_requested_at = now;
+        now.checked_add(crate::constants::PANIC_UNLOCK_COOLDOWN_SECONDS)
+            .ok_or_else(|| error!(crate::error::ErrorCode::Overflow))
+    }
+
+    /// Lift the panic lock once an unlock request has waited out the cooldown.
+    pub fn complete_panic_unlock(&mut self, now: i64) -> Result<()> {
+        require!(
+            self.is_panic_locked() && self.panic_unlock_requested_at != 0,
+            crate::error::ErrorCode::VaultNotPanicLocked
+        );
+        require!(
+            now >= self
+                .panic_unlock_requested_at
+                .saturating_add(crate::constants::PANIC_UNLOCK_COOLDOWN_SECONDS),
+            crate::error::ErrorCode::PanicUnlockCooldownActive
+        );
+        self.panic_locked_at = 0;
+        self.panic_unlock_requested_at = 0;
+        Ok(())
+    }
+
+    /// Fail unless at least `guardian_threshold` distinct guardians signed, counting
+    /// `authority` and the signer `remaining_accounts`.
+    pub fn check_guardians(
+        &self,
+        authority: Pubkey,
+        remaining_accounts: &[AccountInfo],
+    ) -> Result<()> {
+        require!(
+            self.guardian_threshold > 0,
+            crate::error::ErrorCode::RecoveryNotConfigured
+        );
+        let mut seen: std::collections::BTreeSet<Pubkey> = std::collections::BTreeSet::new();
+        if self.guardians.contains(&authority) {
+            seen.insert(authority);
+        }
+        for ai in remaining_accounts.iter() {
+            if ai.is_signer && self.guardians.contains(ai.key) {
+                seen.insert(ai.key());
+            }
+        }
+        require!(
+            seen.len() >= self.guardian_threshold as usize,
+            crate::error::ErrorCode::Unauthorized
+        );
+        Ok(())
+    }
+
+    /// Queue a new guardian set (empty with threshold 0 removes recovery).
+    /// Returns when it takes effect.
+    pub fn propose_guardians(
+        &mut self,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+        now: i64,
+    ) -> Result<i64> {
+        let unique: std::collections::BTreeSet<Pubkey> = guardians.iter().copied().collect();
+        require!(
+            guardians.len() <= MAX_GUARDIANS
+                && unique.len() == guardians.len()
+                && !unique.contains(&self.owner)
+                && (threshold as usize) <= guardians.len()
+                && (threshold == 0) == guardians.is_empty(),
+            crate::error::ErrorCode::InvalidGuardianSet
+        );
+        let effective_at = now
+            .checked_add(GUARDIAN_CHANGE_DELAY_SECONDS)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        self.pending_guardians = Some(PendingGuardianSet {
+            guardians,
+            threshold,
+            effective_at,
+        });
+        Ok(effective_at)
+    }
+
+    /// Install the queued guardian set once its delay has passed.
+    pub fn apply_pending_guardians(&mut self, now: i64) -> Result<()> {
+        let pending = self
+            .pending_guardians
+            .take()
+            .ok_or(crate::error::ErrorCode::NotFound)?;
+        if now < pending.effective_at {
+            self.pending_guardians = Some(pending);
+            return err!(crate::error::ErrorCode::PolicyChangeNotReady);
+        }
+        self.guardians = pending.guardians;
+        self.guardian_threshold = pending.threshold;
+        Ok(())
+    }
+
+    /// Begin rotating the owner to `new_owner`; it can complete after
+    /// `RECOVERY_DELAY_SECONDS` unless the owner vetoes. Returns that time.
+    pub fn start_recovery(&mut self, new_owner: Pubkey, now: i64) -> Result<i64> {
+        require!(
+            self.guardian_threshold > 0,
+            crate::error::ErrorCode::RecoveryNotConfigured
+        );
+        require!(
+            self.pending_recovery.is_none(),
+            crate::error::ErrorCode::RecoveryPending
+        );
+        require!(
+            new_owner != self.owner && new_owner != Pubkey::default(),
+            crate::error::ErrorCode::InvalidRecoveryOwner
+        );
+        let executable_at = now
+            .checked_add(crate::constants::RECOVERY_DELAY_SECONDS)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        self.pending_recovery = Some(PendingRecovery {
+            new_owner,
+            started_at: now,
+            executable_at,
+        });
+        Ok(executable_at)
+    }
+
+    /// Hand the vault to the recovered owner. Everything the old key set up to
+    /// act on its behalf (delegates, multisig, queued policy and guardian
+    /// changes) is dropped; guardians, co-signer and limits stay.
+    pub fn complete_recovery(&mut self, now: i64) -> Result<Pubkey> {
+        let recovery = self
+            .pending_recovery
+            .ok_or(crate::error::ErrorCode::NotFound)?;
+        require!(
+            now >= recovery.executable_at,
+            crate::error::ErrorCode::RecoveryDelayActive
+        );
+        Ok(self.transfer_ownership(recovery.new_owner, now))
+    }
+
+    /// Restart the inheritance clock when the owner is `authority` or one of
+    /// the signer `remaining_accounts`, or when they meet the multisig threshold.
+    pub fn record_owner_activity(
+        &mut self,
+        authority: Pubkey,
+        remaining_accounts: &[AccountInfo],
+        now: i64,
+    ) {
+        if authority == self.owner
+            || remaining_accounts
+                .iter()
+                .any(|ai| ai.is_signer && *ai.key == self.owner)
+            || (self.multisig_threshold > 0
+                && self
+                    .check_signers(authority, remaining_accounts, false)
+                    .is_ok())
+        {
+            self.last_owner_activity_at = now;
+        }
+    }
+
+    /// Name the key that inherits the vault after `inactivity_period_seconds`
+    /// without owner activity, or remove it with `None`.
+    pub fn set_beneficiary(
+        &mut self,
+        beneficiary: Option<Pubkey>,
+        inactivity_period_seconds: i64,
+    ) -> Result<()> {
+        match beneficiary {
+            Some(key) => {
+                require!(
+                    key != self.owner
+                        && key != Pubkey::default()
+                        && inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS,
+                    crate::error::ErrorCode::InvalidBeneficiary
+                );
+                self.inactivity_period_seconds = inactivity_period_seconds;
+            }
+            None => self.inactivity_period_seconds = 0,
+        }
+        self.beneficiary = beneficiary;
+        Ok(())
+    }
+
+    /// Whether `key` is the beneficiary and the owner has been inactive for
+    /// the full inactivity period.
+    pub fn can_inherit(&self, key: &Pubkey, now: i64) -> bool {
+        self.beneficiary == Some(*key)
+            && now
+                >= self
+                    .last_owner_activity_at
+                    .saturating_add(self.inactivity_period_seconds)
+    }
+
+    /// Make the beneficiary the owner, resetting the signing setup as a
+    /// recovery does. Returns the previous owner.
+    pub fn claim_inheritance(&mut self, now: i64) -> Result<Pubkey> {
+        let beneficiary = self.beneficiary.ok_or(crate::error::ErrorCode::NotFound)?;
+        require!(
+            self.can_inherit(&beneficiary, now),
+            crate::error::ErrorCode::InheritanceNotClaimable
+        );
+        Ok(self.transfer_ownership(beneficiary, now))
+    }
+
+    fn transfer_ownership(&mut self, new_owner: Pubkey, now: i64) -> Pubkey {
+        let old_owner = self.owner;
+        self.owner = new_owner;
+        self.last_owner_activity_at = now;
+        self.pending_recovery = None;
+        self.delegates.clear();
+        self.multisig_threshold = 0;
+        self.multisig_signers.clear();
+        self.pending_policy_changes.clear();
+        self.pending_guardians = None;
+        self.pending_co_signer = None;
+        // A guardian or beneficiary that becomes owner stops being one
+        if let Some(i) = self.guardians.iter().position(|g| *g == new_owner) {
+            self.guardians.swap_remove(i);
+            self.guardian_threshold = self.guardian_threshold.min(self.guardians.len() as u8);
+        }
+        if self.beneficiary == Some(new_owner) {
+            self.beneficiary = None;
+            self.inactivity_period_seconds = 0;
+        }
+        old_owner
+    }
+
+    /// Fail unless the owner approved `program` as an integrator on this vault.
+    pub fn require_integrator(&self, program: Pubkey) -> Result<()> {
+        require!(
+            self.integrators.iter().any(|i| i.program == program),
+            crate::error::ErrorCode::IntegratorNotApproved
+        );
+        Ok(())
+    }
+
+    /// Book `amount` newly locked by `program`, enforcing its per-vault cap.
+    pub fn record_integrator_lock(&mut self, program: Pubkey, amount: u64) -> Result<()> {
+        let entry = self
+            .integrators
+            .iter_mut()
+            .find(|i| i.program == program)
+            .ok_or(crate::error::ErrorCode::IntegratorNotApproved)?;
+        let locked = entry
+            .locked
+            .checked_add(amount)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        if let Some(max) = entry.max_locked {
+            require!(
+                locked <= max,
+                crate::error::ErrorCode::IntegratorCapExceeded
+            );
+        }
+        entry.locked = locked;
+        Ok(())
+    }
+
+    /// Release `amount` of the collateral `program` holds locked. A program may
+    /// only unlock what it locked itself.
+    pub fn record_integrator_unlock(&mut self, program: Pubkey, amount: u64) -> Result<()> {
+        let entry = self
+            .integrators
+            .iter_mut()
+            .find(|i| i.program == program)
+            .ok_or(crate::error::ErrorCode::IntegratorNotApproved)?;
+        entry.locked = entry
+            .locked
+            .checked_sub(amount)
+            .ok_or(crate::error::ErrorCode::InsufficientFunds)?;
+        if entry.locked == 0 {
+            entry.lease_expires_at = 0;
+        }
+        Ok(())
+    }
+
+    /// Extend `program`'s lease on its locks in this vault to `now + lease_seconds`.
+    /// Returns the new expiry.
+    pub fn renew_integrator_lease(
+        &mut self,
+        program: Pubkey,
+        now: i64,
+        lease_seconds: i64,
+    ) -> Result<i64> {
+        require!(
+            lease_seconds > 0 && lease_seconds <= crate::constants::MAX_LOCK_LEASE_SECONDS,
+            crate::error::ErrorCode::InvalidLeaseDuration
+        );
+        let entry = self
+            .integrators
+            .iter_mut()
+            .find(|i| i.program == program)
+            .ok_or(crate::error::ErrorCode::IntegratorNotApproved)?;
+        entry.lease_expires_at = now
+            .checked_add(lease_seconds)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        Ok(entry.lease_expires_at)
+    }
+
+    /// Move everything `program` holds locked back to available, keeping its
+    /// approval. Returns the amount released.
+    pub fn release_integrator_lock(&mut self, program: Pubkey) -> Result<u64> {
+        let entry = self
+            .integrators
+            .iter_mut()
+            .find(|i| i.program == program)
+            .ok_or(crate::error::ErrorCode::NotFound)?;
+        let amount = entry.locked;
+        entry.locked = 0;
+        entry.lease_expires_at = 0;
+        self.locked_balance = self
+            .locked_balance
+            .checked_sub(amount)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        self.available_balance = self
+            .available_balance
+            .checked_add(amount)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        Ok(amount)
+    }
+
+    /// Drop `program`'s approval and move everything it still holds locked back
+    /// to available. Used once a deprecated integrator's grace period is over.
+    pub fn release_stranded_lock(&mut self, program: Pubkey) -> Result<u64> {
+        let amount = self.release_integrator_lock(program)?;
+        self.integrators.retain(|i| i.program != program);
+        Ok(amount)
+    }
+
+    /// Locked collateral no integrator approval accounts for: locks taken by a
+    /// version-1 deployment, before locks were attributed per integrator.
+    pub fn unattributed_locked(&self) -> u64 {
+        let attributed = self
+            .integrators
+            .iter()
+            .fold(0u64, |sum, i| sum.saturating_add(i.locked));
+        self.locked_balance.saturating_sub(attributed)
+    }
+
+    /// Move all unattributed locked collateral back to available.
+    pub fn release_unattributed_lock(&mut self) -> Result<u64> {
+        let amount = self.unattributed_locked();
+        require!(amount > 0, crate::error::ErrorCode::NotFound);
+        self.locked_balance = self
+            .locked_balance
+            .checked_sub(amount)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        self.available_balance = self
+            .available_balance
+            .checked_add(amount)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        Ok(amount)
+    }
+
+    /// Co-signer whose approval moving `amount` out needs, if any.
+    pub fn co_signer_for(&self, amount: u64) -> Option<Pubkey> {
+        self.co_signer
+            .filter(|p| amount > p.threshold)
+            .map(|p| p.signer)
+    }
+
+    /// Check a signed co-signer approval against an outflow of `amount` to
+    /// `recipient` and consume its nonce.
+    pub fn accept_co_signer_approval(
+        &mut self,
+        approval: &CoSignerApproval,
+        amount: u64,
+        recipient: Pubkey,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            approval.amount == amount && approval.recipient == recipient,
+            crate::error::ErrorCode::CoSignerApprovalInvalid
+        );
+        require!(
+            now <= approval.expires_at
+                && approval.expires_at
+                    <= now.saturating_add(crate::constants::MAX_CO_SIGNER_APPROVAL_TTL_SECONDS),
+            crate::error::ErrorCode::CoSignerApprovalExpired
+        );
+        require!(
+            approval.nonce > self.co_signer_nonce,
+            crate::error::ErrorCode::CoSignerApprovalReplayed
+        );
+        self.co_signer_nonce = approval.nonce;
+        Ok(())
+    }
+
+    /// Queue a co-signer policy change (None removes the policy). Setting a policy
+    /// on a vault without one takes effect at once; anything else waits
+    /// `CO_SIGNER_POLICY_DELAY_SECONDS`. Returns when the change takes effect.
+    pub fn propose_co_signer_policy(
+        &mut self,
+        policy: Option<CoSignerPolicy>,
+        now: i64,
+    ) -> Result<i64> {
+        if self.co_signer.is_none() && policy.is_some() {
+            self.co_signer = policy;
+            self.pending_co_signer = None;
+            return Ok(now);
+        }
+        let effective_at = now
+            .checked_add(crate::constants::CO_SIGNER_POLICY_DELAY_SECONDS)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        self.pending_co_signer = Some(PendingCoSignerPolicy {
+            policy,
+            effective_at,
+        });
+        Ok(effective_at)
+    }
+
+    /// Apply the queued co-signer policy change once its delay has passed.
+    pub fn apply_pending_co_signer_policy(&mut self, now: i64) -> Result<Option<CoSignerPolicy>> {
+        let pending = self
+            .pending_co_signer
+            .ok_or(crate::error::ErrorCode::NotFound)?;
+        require!(
+            now >= pending.effective_at,
+            crate::error::ErrorCode::PolicyChangeNotReady
+        );
+        self.co_signer = pending.policy;
+        self.pending_co_signer = None;
+        Ok(self.co_signer)
+    }
+
+    /// Whether `change` would loosen the vault's current protection: a shorter
+    /// withdraw delay, a higher or disabled rate limit, a new whitelist address
+    /// or delegate, or a multisig with a lower threshold (single-owner mode
+    /// counts as 1) or enough new keys to reach the threshold on their own.
+    pub fn is_policy_relaxation(&self, change: &PolicyChange) -> bool {
+        match change {
+            PolicyChange::MinWithdrawDelay { seconds } => {
+                *seconds < self.min_withdraw_delay_seconds
+            }
+            PolicyChange::RateLimit {
+                window_seconds,
+                max_amount,
+            } => {
+                let enabled = self.rate_window_seconds > 0 && self.rate_limit_amount > 0;
+                let stays_enabled = *window_seconds > 0 && *max_amount > 0;
+                enabled
+                    && (!stays_enabled
+                        || *max_amount > self.rate_limit_amount
+                        || *window_seconds < self.rate_window_seconds)
+            }
+            PolicyChange::AddWhitelist { address } => !self.withdraw_whitelist.contains(address),
+            PolicyChange::Multisig { signers, threshold } => {
+                if *threshold == 0 {
+                    return self.multisig_threshold > 0;
+                }
+                let (current_threshold, authorized) = if self.multisig_threshold == 0 {
+                    let mut keys = self.delegates.clone();
+                    keys.push(self.owner);
+                    (1, keys)
+                } else {
+                    (self.multisig_threshold, self.multisig_signers.clone())
+                };
+                let new_keys = signers.iter().filter(|k| !authorized.contains(k)).count();
+                *threshold < current_threshold || new_keys >= *threshold as usize
+            }
+            PolicyChange::AddDelegate { delegate } => !self.delegates.contains(delegate),
+        }
+    }
+
+    /// How long a relaxation waits: the vault's own withdraw delay or the
+    /// governance floor, whichever is longer.
+    pub fn policy_change_delay(&self, floor_seconds: i64) -> i64 {
+        self.min_withdraw_delay_seconds.max(floor_seconds)
+    }
+
+    /// Queue `change` to apply at `now + delay`, replacing a pending change to the
+    /// same setting. Returns when it takes effect.
+    pub fn queue_policy_change(
+        &mut self,
+        change: PolicyChange,
+        now: i64,
+        delay: i64,
+    ) -> Result<i64> {
+        self.pending_policy_changes
+            .retain(|p| !p.change.superseded_by(&change) && p.change != change);
+        require!(
+            self.pending_policy_changes.len() < MAX_PENDING_POLICY_CHANGES,
+            crate::error::ErrorCode::Overflow
+        );
+        let effective_at = now
+            .checked_add(delay)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        self.pending_policy_changes.push(PendingPolicyChange {
+            change,
+            effective_at,
+        });
+        Ok(effective_at)
+    }
+
+    /// Remove and return the queued changes whose delay has passed, oldest first.
+    pub fn take_matured_policy_changes(&mut self, now: i64) -> Vec<PolicyChange> {
+        let (matured, waiting): (Vec<_>, Vec<_>) = self
+            .pending_policy_changes
+            .drain(..)
+            .partition(|p| p.effective_at <= now);
+        self.pending_policy_changes = waiting;
+        matured.into_iter().map(|p| p.change).collect()
+    }
+
+    /// Available balance the owner wants kept liquid: `min_liquid_reserve_bps` of total.
+    pub fn liquid_reserve_target(&self) -> u64 {
+        ((self.total_balance as u128) * (self.min_liquid_reserve_bps as u128)
+            / crate::constants::BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Accrue custody fees since the last accrual into `custody_fee_owed` and
+    /// return the newly accrued amount. The fee is charged on `total_balance` at
+    /// accrual time; the first accrual and exempt tiers only move the snapshot.
+    pub fn accrue_custody_fee(&mut self, va: &VaultAuthority, now: i64) -> Result<u64> {
+        let index = va.custody_fee_index_at(now);
+        let fee =
+            if self.last_fee_accrual_at == 0 || va.is_custody_fee_exempt(self.risk_tier_override) {
+                0
+            } else {
+                let delta = index.saturating_sub(self.custody_fee_index_snapshot);
+                let fee = (self.total_balance as u128) * (delta as u128)
+                    / (crate::constants::BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+                u64::try_from(fee).map_err(|_| error!(crate::error::ErrorCode::Overflow))?
+            };
+        self.custody_fee_owed = self
+            .custody_fee_owed
+            .checked_add(fee)
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        self.custody_fee_index_snapshot = index;
+        self.last_fee_accrual_at = now;
+        Ok(fee)
+    }
+
+    /// Take as much of `custody_fee_owed` as is available out of the vault's
+    /// balances and return it; the caller moves the tokens to the treasury.
+    pub fn take_custody_fee_owed(&mut self) -> u64 {
+        let collected = self.custody_fee_owed.min(self.available_balance);
+        self.custody_fee_owed -= collected;
+        self.available_balance -= collected;
+        self.total_balance -= collected;
+        collected
+    }
+
+    /// Invariant: total = locked + available + timelocked + deployed in yield
+    /// + pending yield redemptions.
+    pub fn check_balance_invariant(&self) -> Result<()> {
+        let accounted = self
+            .locked_balance
+            .checked_add(self.available_balance)
+            .and_then(|v| v.checked_add(self.timelocked_balance().ok()?))
+            .and_then(|v| v.checked_add(self.yield_deposited_balance))
+            .and_then(|v| v.checked_add(self.pending_redemption_balance))
+            .ok_or(crate::error::ErrorCode::Overflow)?;
+        require!(
+            self.total_balance == accounted,
+            crate::error::ErrorCode::InvariantViolation
+        );
+        Ok(())
+    }
+
     pub const LEN: usize = 8  // discriminator
         + 32  // owner
         + 32  // token_account
@@ -81,8 +785,19 @@ impl CollateralVault {
         + 8   // yield_accrued_balance
         + 8   // last_compounded_at
         + 32  // active_yield_program
+        + 8   // last_harvested_at
+        + 2   // yield_target_weight_bps
+        + 8   // pending_redemption_balance
+        + 2   // min_liquid_reserve_bps
+        + 8   // yield_pullback_pending
+        + 1   // auto_compound_opt_out
+        + 1   // strict_withdrawals
+        + 8   // last_fee_accrual_at
+        + 8   // custody_fee_index_snapshot
+        + 8   // custody_fee_owed
         + 8   // created_at
         + 1   // bump
+        + 32  // original_owner
         + 1   // multisig_threshold
         + 4 + (MAX_MULTISIG_SIGNERS * 32) // multisig_signers vec
         + 4 + (MAX_DELEGATES * 32)        // delegates vec
@@ -94,5 +809,21 @@ impl CollateralVault {
         + 8   // rate_limit_amount
         + 8   // last_withdrawal_window_start
         + 8   // withdrawn_in_window
-        + 64; // reserved
+        + 1 + 1 // risk_tier_override
+        + 4 + (MAX_VAULT_INTEGRATORS * IntegratorApproval::LEN) // integrators vec
+        + 1 + CoSignerPolicy::LEN // co_signer
+        + 1 + PendingCoSignerPolicy::LEN // pending_co_signer
+        + 8   // co_signer_nonce
+        + 4 + (MAX_PENDING_POLICY_CHANGES * PendingPolicyChange::LEN) // pending_policy_changes vec
+        + 8   // panic_locked_at
+        + 8   // panic_unlock_requested_at
+        + 4 + (MAX_GUARDIANS * 32) // guardians vec
+        + 1   // guardian_threshold
+        + 1 + PendingGuardianSet::LEN // pending_guardians
+        + 1 + PendingRecovery::LEN // pending_recovery
+        + 1 + 32 // beneficiary
+        + 8   // inactivity_period_seconds
+        + 8   // last_owner_activity_at
+        + 1   // layout_version
+        + 63; // reserved
 }
@@ -0,0 +1,106 @@
+use crate::constants::MAX_INTEGRATOR_NAME_LEN;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+// Capability flags an integrator may be granted
+pub const CAP_LOCK: u8 = 1 << 0;
+pub const CAP_UNLOCK: u8 = 1 << 1;
+pub const CAP_TRANSFER: u8 = 1 << 2;
+pub const CAP_LIQUIDATE: u8 = 1 << 3;
+pub const CAP_SETTLE: u8 = 1 << 4;
+pub const CAP_ALL: u8 = CAP_LOCK | CAP_UNLOCK | CAP_TRANSFER | CAP_LIQUIDATE | CAP_SETTLE;
+
+#[account]
+#[derive(InitSpace)]
+pub struct IntegratorRegistry {
+    // Program id of the integrator this record describes
+    pub program: Pubkey, // 32
+    // Bitset of CAP_* flags
+    pub capabilities: u8, // 1
+    // Disabled integrators are refused by every CPI entry point
+    pub enabled: bool, // 1
+
+    // Protocol-wide cap on collateral this program may hold locked (None = uncapped)
+    pub max_total_locked: Option<u64>, // 1 + 8
+    // Collateral this program currently holds locked across all vaults
+    pub total_locked: u64, // 8
+
+    // Metadata
+    #[max_len(MAX_INTEGRATOR_NAME_LEN)]
+    pub name: String, // 4 + 32
+    pub version: u32,       // 4
+    pub registered_at: i64, // 8
+    pub updated_at: i64,    // 8
+
+    // Wind-down: once deprecated the program may only unlock; after the grace
+    // period owners can reclaim whatever it still holds locked (0 = active)
+    pub deprecated_at: i64,        // 8
+    pub grace_period_ends_at: i64, // 8
+
+    // Key whose ed25519-signed risk attestations stand in for position summaries
+    pub risk_signer: Option<Pubkey>, // 1 + 32
+
+    pub bump: u8, // 1
+
+    pub _reserved: [u8; 32], // 32
+}
+
+impl IntegratorRegistry {
+    pub const LEN: usize = 8  // discriminator
+        + 32                  // program
+        + 1                   // capabilities
+        + 1                   // enabled
+        + 1 + 8               // max_total_locked
+        + 8                   // total_locked
+        + 4 + MAX_INTEGRATOR_NAME_LEN // name
+        + 4                   // version
+        + 8                   // registered_at
+        + 8                   // updated_at
+        + 8                   // deprecated_at
+        + 8                   // grace_period_ends_at
+        + 1 + 32              // risk_signer
+        + 1                   // bump
+        + 32; // reserved
+
+    /// Fail unless this record belongs to `caller`, is enabled and grants `capability`.
+    pub fn require_capability(&self, caller: Pubkey, capability: u8) -> Result<()> {
+        require_keys_eq!(self.program, caller, ErrorCode::UnauthorizedProgram);
+        require!(self.enabled, ErrorCode::IntegratorDisabled);
+        // A deprecated integrator can only release what it holds
+        require!(
+            !self.is_deprecated() || capability == CAP_UNLOCK,
+            ErrorCode::IntegratorDeprecated
+        );
+        require!(
+            self.capabilities & capability == capability,
+            ErrorCode::MissingCapability
+        );
+        Ok(())
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated_at != 0
+    }
+
+    /// True once the integrator is deprecated and its grace period has ended.
+    pub fn is_wound_down(&self, now: i64) -> bool {
+        self.is_deprecated() && now >= self.grace_period_ends_at
+    }
+
+    /// Book `amount` newly locked by this integrator against its protocol-wide cap.
+    pub fn record_lock(&mut self, amount: u64) -> Result<()> {
+        let total = self
+            .total_locked
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        if let Some(max) = self.max_total_locked {
+            require!(total <= max, ErrorCode::IntegratorExposureExceeded);
+        }
+        self.total_locked = total;
+        Ok(())
+    }
+
+    pub fn record_unlock(&mut self, amount: u64) {
+        self.total_locked = self.total_locked.saturating_sub(amount);
+    }
+}
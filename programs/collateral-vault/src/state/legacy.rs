@@ -0,0 +1,210 @@
+//! Account layouts written by the original deployment (layout version 1).
+//!
+//! Fields have since been added to `CollateralVault` and `VaultAuthority`,
+//! and `VaultAuthority.authorized_programs` was removed in favour of integrator
+//! registry records. Version-1 accounts therefore no longer deserialize as the
+//! current types. `migrate_vault_authority` and `migrate_vault` read them
+//! through these structs, resize the account and write the current layout.
+//! Version-1 accounts carry no version byte and are recognised by their size.
+
+use crate::constants::{
+    ACCOUNT_LAYOUT_VERSION, MAX_AUTHORIZED_PROGRAMS, MAX_DELEGATES, MAX_MULTISIG_SIGNERS,
+    MAX_PENDING_WITHDRAWALS, MAX_TIMELOCKS, MAX_WITHDRAW_WHITELIST,
+};
+use crate::state::{CollateralVault, VaultAuthority};
+use crate::types::{FeeSchedule, PendingWithdrawalEntry, TimelockEntry};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyVaultAuthority {
+    pub governance: Pubkey,
+    pub authorized_programs: Vec<Pubkey>,
+    pub bump: u8,
+    pub freeze: bool,
+    pub cpi_enforced: bool,
+    pub yield_whitelist: Vec<Pubkey>,
+    pub risk_level: u8,
+    pub _reserved: [u8; 64],
+}
+
+impl LegacyVaultAuthority {
+    pub const LEN: usize = 8  // discriminator
+        + 32                  // governance
+        + 4                   // vec length prefix
+        + (MAX_AUTHORIZED_PROGRAMS * 32)
+        + 1                   // bump
+        + 1                   // freeze
+        + 1                   // cpi_enforced
+        + 4                   // yield_whitelist vec len
+        + (MAX_AUTHORIZED_PROGRAMS * 32)
+        + 1                   // risk_level
+        + 64; // reserved
+
+    /// Decode a version-1 account, or `None` if `data` is not one.
+    pub fn try_from_data(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN || !data.starts_with(VaultAuthority::DISCRIMINATOR) {
+            return None;
+        }
+        Self::deserialize(&mut &data[8..]).ok()
+    }
+
+    /// The current layout with every field added since version 1 at its
+    /// `initialize_vault_authority` default. The CPI allowlist is dropped:
+    /// integrators are registered with `register_integrator` instead.
+    pub fn upgrade(self) -> VaultAuthority {
+        VaultAuthority {
+            governance: self.governance,
+            bump: self.bump,
+            freeze: self.freeze,
+            cpi_enforced: self.cpi_enforced,
+            yield_whitelist: self.yield_whitelist,
+            risk_level: self.risk_level,
+            risk_params: vec![],
+            total_value_locked: 0,
+            compound_interval_seconds: 0,
+            performance_fee_bps: 0,
+            keeper_bounty_bps: 0,
+            withdraw_fee: FeeSchedule::default(),
+            transfer_fee: FeeSchedule::default(),
+            emergency_withdraw_fee: FeeSchedule::default(),
+            custody_fee_bps: 0,
+            custody_fee_index: 0,
+            custody_fee_index_updated_at: 0,
+            custody_fee_exempt_tiers: 0,
+            lock_lease_grace_seconds: 0,
+            free_margin_withdrawals: false,
+            policy_change_delay_floor_seconds: 0,
+            layout_version: ACCOUNT_LAYOUT_VERSION,
+            _reserved: [0u8; 63],
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyCollateralVault {
+    pub owner: Pubkey,
+    pub token_account: Pubkey,
+    pub usdt_mint: Pubkey,
+    pub total_balance: u64,
+    pub locked_balance: u64,
+    pub available_balance: u64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub yield_deposited_balance: u64,
+    pub yield_accrued_balance: u64,
+    pub last_compounded_at: i64,
+    pub active_yield_program: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+    pub multisig_threshold: u8,
+    pub multisig_signers: Vec<Pubkey>,
+    pub delegates: Vec<Pubkey>,
+    pub timelocks: Vec<TimelockEntry>,
+    pub min_withdraw_delay_seconds: i64,
+    pub pending_withdrawals: Vec<PendingWithdrawalEntry>,
+    pub withdraw_whitelist: Vec<Pubkey>,
+    pub rate_window_seconds: u32,
+    pub rate_limit_amount: u64,
+    pub last_withdrawal_window_start: i64,
+    pub withdrawn_in_window: u64,
+    pub _reserved: [u8; 64],
+}
+
+impl LegacyCollateralVault {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // token_account
+        + 32  // usdt_mint
+        + 8   // total_balance
+        + 8   // locked_balance
+        + 8   // available_balance
+        + 8   // total_deposited
+        + 8   // total_withdrawn
+        + 8   // yield_deposited_balance
+        + 8   // yield_accrued_balance
+        + 8   // last_compounded_at
+        + 32  // active_yield_program
+        + 8   // created_at
+        + 1   // bump
+        + 1   // multisig_threshold
+        + 4 + (MAX_MULTISIG_SIGNERS * 32) // multisig_signers vec
+        + 4 + (MAX_DELEGATES * 32)        // delegates vec
+        + 4 + (MAX_TIMELOCKS * (8 + 8))   // timelocks vec (u64 + i64)
+        + 8   // min_withdraw_delay_seconds
+        + 4 + (MAX_PENDING_WITHDRAWALS * (8 + 8 + 8)) // pending_withdrawals vec
+        + 4 + (MAX_WITHDRAW_WHITELIST * 32) // withdraw_whitelist vec
+        + 4   // rate_window_seconds
+        + 8   // rate_limit_amount
+        + 8   // last_withdrawal_window_start
+        + 8   // withdrawn_in_window
+        + 64; // reserved
+
+    /// Decode a version-1 account, or `None` if `data` is not one.
+    pub fn try_from_data(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN || !data.starts_with(CollateralVault::DISCRIMINATOR) {
+            return None;
+        }
+        Self::deserialize(&mut &data[8..]).ok()
+    }
+
+    /// The current layout with every field added since version 1 at its
+    /// `initialize_vault` default. The inactivity clock starts at `now`.
+    pub fn upgrade(self, now: i64) -> CollateralVault {
+        CollateralVault {
+            owner: self.owner,
+            token_account: self.token_account,
+            usdt_mint: self.usdt_mint,
+            total_balance: self.total_balance,
+            locked_balance: self.locked_balance,
+            available_balance: self.available_balance,
+            total_deposited: self.total_deposited,
+            total_withdrawn: self.total_withdrawn,
+            yield_deposited_balance: self.yield_deposited_balance,
+            yield_accrued_balance: self.yield_accrued_balance,
+            last_compounded_at: self.last_compounded_at,
+            active_yield_program: self.active_yield_program,
+            last_harvested_at: 0,
+            yield_target_weight_bps: 0,
+            pending_redemption_balance: 0,
+            min_liquid_reserve_bps: 0,
+            yield_pullback_pending: 0,
+            auto_compound_opt_out: false,
+            strict_withdrawals: false,
+            last_fee_accrual_at: 0,
+            custody_fee_index_snapshot: 0,
+            custody_fee_owed: 0,
+            created_at: self.created_at,
+            bump: self.bump,
+            original_owner: self.owner,
+            multisig_threshold: self.multisig_threshold,
+            multisig_signers: self.multisig_signers,
+            delegates: self.delegates,
+            timelocks: self.timelocks,
+            min_withdraw_delay_seconds: self.min_withdraw_delay_seconds,
+            pending_withdrawals: self.pending_withdrawals,
+            withdraw_whitelist: self.withdraw_whitelist,
+            rate_window_seconds: self.rate_window_seconds,
+            rate_limit_amount: self.rate_limit_amount,
+            last_withdrawal_window_start: self.last_withdrawal_window_start,
+            withdrawn_in_window: self.withdrawn_in_window,
+            risk_tier_override: None,
+            integrators: vec![],
+            co_signer: None,
+            pending_co_signer: None,
+            co_signer_nonce: 0,
+            pending_policy_changes: vec![],
+            panic_locked_at: 0,
+            panic_unlock_requested_at: 0,
+            guardians: vec![],
+            guardian_threshold: 0,
+            pending_guardians: None,
+            pending_recovery: None,
+            beneficiary: None,
+            inactivity_period_seconds: 0,
+            last_owner_activity_at: now,
+            layout_version: ACCOUNT_LAYOUT_VERSION,
+            _reserved: [0u8; 63],
+        }
+    }
+}
@@ -1,5 +1,17 @@
+pub mod collateral_config;
 pub mod collateral_vault;
+pub mod integrator_registry;
+pub mod legacy;
 pub mod vault_authority;
+pub mod yield_position;
+pub mod yield_redemption;
+pub mod yield_strategy;
 
+pub use collateral_config::CollateralConfig;
 pub use collateral_vault::CollateralVault;
+pub use integrator_registry::IntegratorRegistry;
+pub use legacy::{LegacyCollateralVault, LegacyVaultAuthority};
 pub use vault_authority::VaultAuthority;
+pub use yield_position::YieldPosition;
+pub use yield_redemption::YieldRedemption;
+pub use yield_strategy::YieldStrategy;
@@ -1,4 +1,5 @@
-use crate::constants::MAX_AUTHORIZED_PROGRAMS;
+use crate::constants::{MAX_AUTHORIZED_PROGRAMS, MAX_RISK_LEVELS};
+use crate::types::{FeeSchedule, RiskParams};
 use anchor_lang::prelude::*;
 
 #[account]
@@ -7,10 +8,6 @@ pub struct VaultAuthority {
     // Governance signer allowed to update this authority account
     pub governance: Pubkey, // 32
 
-    // List of programs allowed to call lock/unlock/transfer via CPI
-    #[max_len(MAX_AUTHORIZED_PROGRAMS)]
-    pub authorized_programs: Vec<Pubkey>, // 4 + N*32
-
     pub bump: u8,     // 1
     pub freeze: bool, // 1 (optional global freeze switch)
     // When true, lock/unlock/transfer require CPI-origin check against an allowlisted program
@@ -20,22 +17,96 @@ pub struct VaultAuthority {
     #[max_len(MAX_AUTHORIZED_PROGRAMS)]
     pub yield_whitelist: Vec<Pubkey>, // 4 + N*32
 
-    // Optional risk policy level (0=conservative ... 255=aggressive)
+    // Default risk level applied to vaults without an override (0=conservative ... aggressive)
     pub risk_level: u8, // 1
 
-    pub _reserved: [u8; 64], // 64
+    // Risk parameters indexed by level; levels without an entry are unconstrained
+    #[max_len(MAX_RISK_LEVELS)]
+    pub risk_params: Vec<RiskParams>, // 4 + N*size(RiskParams)
+
+    // Net collateral deposited across all vaults (deposits - withdrawals)
+    pub total_value_locked: u64, // 8
+
+    // Auto-compound crank: minimum time between compounds (0 = crank disabled)
+    pub compound_interval_seconds: i64, // 8
+    // Share of each crank's gain sent to the treasury (bps)
+    pub performance_fee_bps: u16, // 2
+    // Share of each crank's gain paid to the calling keeper (bps)
+    pub keeper_bounty_bps: u16, // 2
+
+    // Per-operation fees paid to the treasury
+    pub withdraw_fee: FeeSchedule,           // 10
+    pub transfer_fee: FeeSchedule,           // 10
+    pub emergency_withdraw_fee: FeeSchedule, // 10
+
+    // Annual custody fee on vault balances (bps per year)
+    pub custody_fee_bps: u16, // 2
+    // Cumulative custody fee index (bps x seconds) as of custody_fee_index_updated_at
+    pub custody_fee_index: u64,            // 8
+    pub custody_fee_index_updated_at: i64, // 8
+    // Bit i set: vaults on risk tier i pay no custody fee
+    pub custody_fee_exempt_tiers: u8, // 1
+
+    // How long past a lapsed lock lease the owner must wait before releasing it
+    pub lock_lease_grace_seconds: i64, // 8
+
+    // When true, vaults may withdraw while positions are open as long as the
+    // remaining collateral covers the integrators' reported required margin
+    pub free_margin_withdrawals: bool, // 1
+
+    // Minimum wait before a vault's security relaxations take effect
+    pub policy_change_delay_floor_seconds: i64, // 8
+
+    // Account layout version (1 = original deployment, which had no such byte)
+    pub layout_version: u8, // 1
+
+    pub _reserved: [u8; 63], // 63
 }
 
 impl VaultAuthority {
     pub const LEN: usize = 8  // discriminator
         + 32                  // governance
-        + 4                   // vec length prefix
-        + (MAX_AUTHORIZED_PROGRAMS * 32)
         + 1                   // bump
         + 1                   // freeze
         + 1                   // cpi_enforced
         + 4                   // yield_whitelist vec len
         + (MAX_AUTHORIZED_PROGRAMS * 32)
         + 1                   // risk_level
-        + 64; // reserved
+        + 4                   // risk_params vec len
+        + (MAX_RISK_LEVELS * RiskParams::LEN)
+        + 8                   // total_value_locked
+        + 8                   // compound_interval_seconds
+        + 2                   // performance_fee_bps
+        + 2                   // keeper_bounty_bps
+        + FeeSchedule::LEN * 3 // withdraw / transfer / emergency_withdraw fees
+        + 2                   // custody_fee_bps
+        + 8                   // custody_fee_index
+        + 8                   // custody_fee_index_updated_at
+        + 1                   // custody_fee_exempt_tiers
+        + 8                   // lock_lease_grace_seconds
+        + 1                   // free_margin_withdrawals
+        + 8                   // policy_change_delay_floor_seconds
+        + 1                   // layout_version
+        + 63; // reserved
+
+    /// Resolve the risk parameters for a vault: its governance override tier if
+    /// set, otherwise the global default level.
+    pub fn risk_params_for(&self, tier_override: Option<u8>) -> Option<RiskParams> {
+        let level = tier_override.unwrap_or(self.risk_level) as usize;
+        self.risk_params.get(level).copied()
+    }
+
+    /// Custody fee index at `now`: the stored index plus the current rate over
+    /// the time since it was last checkpointed.
+    pub fn custody_fee_index_at(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.custody_fee_index_updated_at).max(0) as u64;
+        self.custody_fee_index
+            .saturating_add((self.custody_fee_bps as u64).saturating_mul(elapsed))
+    }
+
+    /// Whether a vault on `tier_override` (or the default level) is exempt from custody fees.
+    pub fn is_custody_fee_exempt(&self, tier_override: Option<u8>) -> bool {
+        let level = tier_override.unwrap_or(self.risk_level);
+        (level as usize) < MAX_RISK_LEVELS && self.custody_fee_exempt_tiers & (1 << level) != 0
+    }
 }
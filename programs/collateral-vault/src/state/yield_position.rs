@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::CollateralVault;
+
+#[account]
+#[derive(InitSpace)]
+pub struct YieldPosition {
+    // Vault this position belongs to
+    pub vault: Pubkey, // 32
+    // Whitelisted yield program holding the position
+    pub yield_program: Pubkey, // 32
+    // Vault-owned receipt token account for this strategy (pinned on first deposit)
+    pub receipt_token_account: Pubkey, // 32
+
+    // Principal deployed into this strategy (part of vault.yield_deposited_balance)
+    pub deposited: u64, // 8
+    // Harvested but not yet compounded or claimed (part of vault.yield_accrued_balance)
+    pub accrued: u64, // 8
+    // Owner-set target share of the vault's deployed yield (bps)
+    pub target_weight_bps: u16, // 2
+    // Unix timestamp of the last harvest of this position
+    pub last_harvested_at: i64, // 8
+
+    pub bump: u8, // 1
+
+    pub _reserved: [u8; 32], // 32
+}
+
+impl YieldPosition {
+    pub const LEN: usize = 8  // discriminator
+        + 32                  // vault
+        + 32                  // yield_program
+        + 32                  // receipt_token_account
+        + 8                   // deposited
+        + 8                   // accrued
+        + 2                   // target_weight_bps
+        + 8                   // last_harvested_at
+        + 1                   // bump
+        + 32; // reserved
+
+    /// Bind an uninitialized position to its vault and program, or check the
+    /// receipt token account against the one pinned on first use.
+    pub fn bind(
+        &mut self,
+        vault: Pubkey,
+        yield_program: Pubkey,
+        receipt_token_account: Option<Pubkey>,
+        bump: u8,
+    ) -> Result<()> {
+        if self.vault == Pubkey::default() {
+            self.vault = vault;
+            self.yield_program = yield_program;
+            self.bump = bump;
+        }
+        require_keys_eq!(self.vault, vault, ErrorCode::Unauthorized);
+        require_keys_eq!(self.yield_program, yield_program, ErrorCode::Unauthorized);
+        if let Some(receipt) = receipt_token_account {
+            if self.receipt_token_account == Pubkey::default() {
+                self.receipt_token_account = receipt;
+            }
+            require_keys_eq!(
+                self.receipt_token_account,
+                receipt,
+                ErrorCode::YieldAdapterMismatch
+            );
+        }
+        Ok(())
+    }
+
+    /// Target principal for this position given the vault's total deployed yield.
+    pub fn target_amount(&self, total_deployed: u64) -> u64 {
+        ((total_deployed as u128) * (self.target_weight_bps as u128)
+            / crate::constants::BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Book principal backing `receipts_burned` out of the `receipts_before`
+    /// receipts the position held, pro rata.
+    pub fn principal_for_receipts(&self, receipts_burned: u64, receipts_before: u64) -> u64 {
+        if receipts_before == 0 {
+            return 0;
+        }
+        let burned = core::cmp::min(receipts_burned, receipts_before);
+        ((self.deposited as u128) * (burned as u128) / (receipts_before as u128)) as u64
+    }
+
+    /// Recognize a shortfall against this position's book value: accrued yield
+    /// is written off first (`accrued_first`), the rest reduces principal and
+    /// the vault's `total_balance`. Returns `(principal_loss, accrued_written_off)`.
+    pub fn write_down(
+        &mut self,
+        vault: &mut CollateralVault,
+        shortfall: u64,
+        accrued_first: bool,
+    ) -> Result<(u64, u64)> {
+        let from_accrued = if accrued_first {
+            core::cmp::min(shortfall, self.accrued)
+        } else {
+            0
+        };
+        let principal_loss = shortfall
+            .checked_sub(from_accrued)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            principal_loss <= self.deposited,
+            ErrorCode::InsufficientYieldBalance
+        );
+
+        self.accrued = self
+            .accrued
+            .checked_sub(from_accrued)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.yield_accrued_balance = vault
+            .yield_accrued_balance
+            .checked_sub(from_accrued)
+            .ok_or(ErrorCode::Overflow)?;
+
+        self.deposited = self
+            .deposited
+            .checked_sub(principal_loss)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.yield_deposited_balance = vault
+            .yield_deposited_balance
+            .checked_sub(principal_loss)
+            .ok_or(ErrorCode::Overflow)?;
+        vault.total_balance = vault
+            .total_balance
+            .checked_sub(principal_loss)
+            .ok_or(ErrorCode::Overflow)?;
+
+        Ok((principal_loss, from_accrued))
+    }
+}
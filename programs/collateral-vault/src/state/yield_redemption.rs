@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct YieldRedemption {
+    // Vault the redemption pays back into
+    pub vault: Pubkey, // 32
+    // Strategy the redemption was requested from
+    pub yield_program: Pubkey, // 32
+
+    // Principal taken out of the position when the ticket was opened
+    pub requested_amount: u64, // 8
+    // Paid back to the vault so far (immediately and via claims)
+    pub filled_amount: u64, // 8
+    pub requested_at: i64,  // 8
+    // After this time an unfilled remainder is written off on claim
+    pub expires_at: i64, // 8
+
+    pub bump: u8, // 1
+
+    pub _reserved: [u8; 32], // 32
+}
+
+impl YieldRedemption {
+    pub const LEN: usize = 8  // discriminator
+        + 32                  // vault
+        + 32                  // yield_program
+        + 8                   // requested_amount
+        + 8                   // filled_amount
+        + 8                   // requested_at
+        + 8                   // expires_at
+        + 1                   // bump
+        + 32; // reserved
+
+    pub fn remaining(&self) -> u64 {
+        self.requested_amount.saturating_sub(self.filled_amount)
+    }
+}
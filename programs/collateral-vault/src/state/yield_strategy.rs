@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct YieldStrategy {
+    // Whitelisted yield program this record describes
+    pub yield_program: Pubkey, // 32
+    // Impaired strategies accept no new deposits; withdrawals stay open
+    pub impaired: bool, // 1
+    // Unix timestamp of the last impairment flag change
+    pub impaired_updated_at: i64, // 8
+
+    // Cap on principal deployed into this strategy across all vaults (0 = none)
+    pub max_deposit: u64, // 8
+    // Cap as a share of protocol TVL (bps, 0 = none)
+    pub max_tvl_share_bps: u16, // 2
+    // Principal currently deployed into this strategy across all vaults
+    pub total_deposited: u64, // 8
+
+    pub bump: u8, // 1
+
+    pub _reserved: [u8; 32], // 32
+}
+
+impl YieldStrategy {
+    pub const LEN: usize = 8  // discriminator
+        + 32                  // yield_program
+        + 1                   // impaired
+        + 8                   // impaired_updated_at
+        + 8                   // max_deposit
+        + 2                   // max_tvl_share_bps
+        + 8                   // total_deposited
+        + 1                   // bump
+        + 32; // reserved
+
+    /// Whether the strategy record at `info` (already address-checked by seeds)
+    /// marks the program as impaired. A record that was never created means the
+    /// strategy has no governance flags set.
+    pub fn is_impaired(info: &AccountInfo) -> Result<bool> {
+        Ok(Self::try_load(info)?.is_some_and(|s| s.impaired))
+    }
+
+    /// Load the strategy record at `info` if it has been created.
+    pub fn try_load(info: &AccountInfo) -> Result<Option<YieldStrategy>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+        let data = info.try_borrow_data()?;
+        Ok(Some(YieldStrategy::try_deserialize(&mut &data[..])?))
+    }
+
+    /// Apply principal flowing into (`inflow`) and out of (`outflow`) the
+    /// strategy to the record at `info`, if it exists. Outflows saturate at
+    /// zero for principal deployed before the record was created.
+    pub fn record_flows(info: &AccountInfo, inflow: u64, outflow: u64) -> Result<()> {
+        let Some(mut strategy) = Self::try_load(info)? else {
+            return Ok(());
+        };
+        strategy.apply_flows(inflow, outflow)?;
+        let mut data = info.try_borrow_mut_data()?;
+        strategy.try_serialize(&mut &mut data[..])?;
+        Ok(())
+    }
+
+    pub fn apply_flows(&mut self, inflow: u64, outflow: u64) -> Result<()> {
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(inflow)
+            .ok_or(crate::error::ErrorCode::Overflow)?
+            .saturating_sub(outflow);
+        Ok(())
+    }
+
+    /// Check the strategy caps for a total deployed principal of `deposited_after`
+    /// given the protocol TVL.
+    pub fn check_capacity(&self, deposited_after: u64, protocol_tvl: u64) -> Result<()> {
+        if self.max_deposit > 0 {
+            require!(
+                deposited_after <= self.max_deposit,
+                crate::error::ErrorCode::YieldStrategyCapExceeded
+            );
+        }
+        if self.max_tvl_share_bps > 0 {
+            let limit = (protocol_tvl as u128) * (self.max_tvl_share_bps as u128)
+                / crate::constants::BPS_DENOMINATOR as u128;
+            require!(
+                (deposited_after as u128) <= limit,
+                crate::error::ErrorCode::YieldStrategyCapExceeded
+            );
+        }
+        Ok(())
+    }
+}
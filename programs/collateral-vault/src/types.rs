@@ -1,3 +1,5 @@
+use crate::constants::{BPS_DENOMINATOR, MAX_GUARDIANS, MAX_MULTISIG_SIGNERS};
+use crate::error::ErrorCode;
 use anchor_lang::prelude::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -11,6 +13,7 @@ pub enum TransactionType {
     YieldDeposit,
     YieldWithdraw,
     YieldCompound,
+    Settlement,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -34,15 +37,246 @@ pub struct PendingWithdrawalEntry {
     pub executable_at: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
-pub struct PositionSummary {
+/// Second-factor requirement for large outflows from a vault.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct CoSignerPolicy {
+    // Key that must sign, or sign an approval, for amounts above the threshold
+    pub signer: Pubkey,
+    // Largest amount that moves without the co-signer
+    pub threshold: u64,
+}
+
+impl CoSignerPolicy {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// A co-signer policy change waiting out `CO_SIGNER_POLICY_DELAY_SECONDS`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PendingCoSignerPolicy {
+    // None removes the policy
+    pub policy: Option<CoSignerPolicy>,
+    pub effective_at: i64,
+}
+
+impl PendingCoSignerPolicy {
+    pub const LEN: usize = 1 + CoSignerPolicy::LEN + 8;
+}
+
+/// A vault security setting change that loosens protection and therefore waits
+/// before it applies.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum PolicyChange {
+    MinWithdrawDelay {
+        seconds: i64,
+    },
+    RateLimit {
+        window_seconds: u32,
+        max_amount: u64,
+    },
+    AddWhitelist {
+        address: Pubkey,
+    },
+    // threshold 0 disables the multisig
+    Multisig {
+        #[max_len(MAX_MULTISIG_SIGNERS)]
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+    AddDelegate {
+        delegate: Pubkey,
+    },
+}
+
+impl PolicyChange {
+    /// Whether a newer submission of `other` replaces this pending change.
+    pub fn superseded_by(&self, other: &PolicyChange) -> bool {
+        matches!(
+            (self, other),
+            (Self::MinWithdrawDelay { .. }, Self::MinWithdrawDelay { .. })
+                | (Self::RateLimit { .. }, Self::RateLimit { .. })
+                | (Self::Multisig { .. }, Self::Multisig { .. })
+        )
+    }
+}
+
+/// A guardian set change waiting out `GUARDIAN_CHANGE_DELAY_SECONDS`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct PendingGuardianSet {
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,
+    pub effective_at: i64,
+}
+
+impl PendingGuardianSet {
+    pub const LEN: usize = 4 + MAX_GUARDIANS * 32 + 1 + 8;
+}
+
+/// Owner rotation started by the guardians; the owner may veto it until
+/// `executable_at`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PendingRecovery {
+    pub new_owner: Pubkey,
+    pub started_at: i64,
+    pub executable_at: i64,
+}
+
+impl PendingRecovery {
+    pub const LEN: usize = 32 + 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct PendingPolicyChange {
+    pub change: PolicyChange,
+    pub effective_at: i64,
+}
+
+impl PendingPolicyChange {
+    // Sized for the largest variant (Multisig)
+    pub const LEN: usize = 1 + 4 + MAX_MULTISIG_SIGNERS * 32 + 1 + 8;
+}
+
+/// Per-level risk limits, expressed in basis points of the vault's total balance.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
+pub struct RiskParams {
+    // Max share of total balance that may be locked by integrators
+    pub max_locked_bps: u16,
+    // Max share of total balance that may be deployed into yield programs
+    pub max_yield_bps: u16,
+    // Min share of total balance that must stay available (unlocked, undeployed)
+    pub min_free_buffer_bps: u16,
+}
+
+impl RiskParams {
+    pub const LEN: usize = 2 + 2 + 2;
+
+    pub fn validate(&self) -> Result<()> {
+        let max = BPS_DENOMINATOR as u16;
+        require!(
+            self.max_locked_bps <= max
+                && self.max_yield_bps <= max
+                && self.min_free_buffer_bps <= max,
+            ErrorCode::InvalidRiskParams
+        );
+        Ok(())
+    }
+
+    /// Check the balances a lock would leave behind.
+    pub fn check_lock(&self, total: u64, locked_after: u64, available_after: u64) -> Result<()> {
+        require!(
+            bps_within(locked_after, total, self.max_locked_bps)?,
+            ErrorCode::RiskLimitExceeded
+        );
+        self.check_free_buffer(total, available_after)
+    }
+
+    /// Check the balances a yield deposit would leave behind.
+    pub fn check_yield(&self, total: u64, yield_after: u64, available_after: u64) -> Result<()> {
+        require!(
+            bps_within(yield_after, total, self.max_yield_bps)?,
+            ErrorCode::RiskLimitExceeded
+        );
+        self.check_free_buffer(total, available_after)
+    }
+
+    fn check_free_buffer(&self, total: u64, available_after: u64) -> Result<()> {
+        let required = (total as u128)
+            .checked_mul(self.min_free_buffer_bps as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        let held = (available_after as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(held >= required, ErrorCode::RiskLimitExceeded);
+        Ok(())
+    }
+}
+
+// amount / total <= bps / 10_000, computed without division
+fn bps_within(amount: u64, total: u64, bps: u16) -> Result<bool> {
+    let lhs = (amount as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::Overflow)?;
+    let rhs = (total as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::Overflow)?;
+    Ok(lhs <= rhs)
+}
+
+/// An integrator program the vault owner approved to lock and move collateral.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct IntegratorApproval {
+    pub program: Pubkey,
+    // Most collateral this program may hold locked in the vault (None = uncapped)
+    pub max_locked: Option<u64>,
+    // Collateral this program currently holds locked in the vault
+    pub locked: u64,
+    // When the program's lease on those locks lapses unless renewed (0 = no lease)
+    pub lease_expires_at: i64,
+    // Highest risk attestation nonce accepted for this program on this vault
+    pub attestation_nonce: u64,
+}
+
+impl IntegratorApproval {
+    pub const LEN: usize = 32 + (1 + 8) + 8 + 8 + 8;
+}
+
+/// Fee charged on one kind of outflow: `bps` of the amount, but at least `min`,
+/// and never more than the amount itself.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
+pub struct FeeSchedule {
+    pub bps: u16,
+    pub min: u64,
+}
+
+impl FeeSchedule {
+    pub const LEN: usize = 2 + 8;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.bps <= crate::constants::MAX_FEE_BPS,
+            ErrorCode::InvalidFeeSchedule
+        );
+        Ok(())
+    }
+
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        if self.bps == 0 && self.min == 0 {
+            return 0;
+        }
+        let pct = ((amount as u128) * (self.bps as u128) / (BPS_DENOMINATOR as u128)) as u64;
+        pct.max(self.min).min(amount)
+    }
+}
+
+/// Operations that carry a governance-set fee.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeOperation {
+    Withdraw,
+    Transfer,
+    EmergencyWithdraw,
+}
+
+/// Haircut-adjusted USD valuation of a vault, returned by `get_collateral_value`.
+/// USD amounts use `USD_VALUE_DECIMALS` decimals.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CollateralValuation {
     pub vault: Pubkey,
-    pub owner: Pubkey,
-    pub open_positions: u64,
-    pub locked_amount: u64,
-    pub last_updated_slot: u64,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub price: i64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub usd_value: u64,
+    pub haircut_bps: u16,
+    pub adjusted_usd_value: u64,
 }
 
-impl PositionSummary {
-    pub const LEN: usize = 32 + 32 + 8 + 8 + 8;
+/// One leg of a netted settlement: a signed change to a vault's available balance.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SettlementEntry {
+    pub vault: Pubkey,
+    pub delta: i64,
 }
+
+// Integrator position summaries follow the published interface crate
+pub use position_interface::PositionSummary;
@@ -0,0 +1,170 @@
+//! Yield adapter interface.
+//!
+//! Whitelisted yield programs are called through a fixed set of instructions.
+//! Each instruction is encoded Anchor-style: an 8-byte discriminator
+//! (`sha256("global:<name>")[..8]`) followed by Borsh-encoded arguments, so an
+//! Anchor program exposing functions with these names implements the adapter.
+//!
+//! | Instruction       | Args          | Effect                                                      |
+//! |-------------------|---------------|-------------------------------------------------------------|
+//! | `adapter_deposit` | `amount: u64` | Pull up to `amount` from the vault token account, mint receipts |
+//! | `adapter_withdraw`| `amount: u64` | Burn receipts, return up to `amount` to the vault token account |
+//! | `adapter_harvest` | —             | Reinvest accrued rewards into the vault's position          |
+//! | `adapter_position_value` | —      | View: set return data to the position's value (`u64`, collateral units) |
+//! | `adapter_request_withdraw` | `amount: u64` | Queue a redemption of `amount`; take the receipts, may pay part immediately |
+//! | `adapter_claim_withdraw` | —      | Pay whatever part of the queued redemption has been filled to the vault token account |
+//!
+//! Required accounts, in order, for every instruction:
+//!
+//! 0. `vault` — vault PDA, signer (signed by the vault program), read-only
+//! 1. `vault_token_account` — vault collateral token account, writable
+//! 2. `receipt_token_account` — vault-owned token account holding strategy receipts, writable
+//! 3. `token_program` — SPL Token program
+//! 4. strategy-specific accounts, forwarded from the caller's remaining accounts
+//!
+//! The vault program never trusts the adapter's own accounting: it measures the
+//! vault and receipt token balances before and after each call and books only
+//! the observed movement.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke_signed};
+
+use crate::error::ErrorCode;
+
+pub const ADAPTER_DEPOSIT_DISCRIMINATOR: [u8; 8] = [190, 207, 72, 186, 232, 106, 46, 72];
+pub const ADAPTER_WITHDRAW_DISCRIMINATOR: [u8; 8] = [121, 55, 72, 46, 185, 100, 173, 236];
+pub const ADAPTER_HARVEST_DISCRIMINATOR: [u8; 8] = [156, 240, 240, 83, 135, 151, 192, 30];
+pub const ADAPTER_POSITION_VALUE_DISCRIMINATOR: [u8; 8] = [31, 63, 180, 163, 24, 195, 23, 159];
+pub const ADAPTER_REQUEST_WITHDRAW_DISCRIMINATOR: [u8; 8] = [179, 18, 10, 200, 63, 62, 122, 186];
+pub const ADAPTER_CLAIM_WITHDRAW_DISCRIMINATOR: [u8; 8] = [85, 151, 168, 140, 182, 34, 154, 75];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AdapterInstruction {
+    Deposit { amount: u64 },
+    Withdraw { amount: u64 },
+    Harvest,
+    PositionValue,
+    RequestWithdraw { amount: u64 },
+    ClaimWithdraw,
+}
+
+impl AdapterInstruction {
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(16);
+        match self {
+            AdapterInstruction::Deposit { amount } => {
+                data.extend_from_slice(&ADAPTER_DEPOSIT_DISCRIMINATOR);
+                data.extend_from_slice(&amount.to_le_bytes());
+            }
+            AdapterInstruction::Withdraw { amount } => {
+                data.extend_from_slice(&ADAPTER_WITHDRAW_DISCRIMINATOR);
+                data.extend_from_slice(&amount.to_le_bytes());
+            }
+            AdapterInstruction::Harvest => {
+                data.extend_from_slice(&ADAPTER_HARVEST_DISCRIMINATOR);
+            }
+            AdapterInstruction::PositionValue => {
+                data.extend_from_slice(&ADAPTER_POSITION_VALUE_DISCRIMINATOR);
+            }
+            AdapterInstruction::RequestWithdraw { amount } => {
+                data.extend_from_slice(&ADAPTER_REQUEST_WITHDRAW_DISCRIMINATOR);
+                data.extend_from_slice(&amount.to_le_bytes());
+            }
+            AdapterInstruction::ClaimWithdraw => {
+                data.extend_from_slice(&ADAPTER_CLAIM_WITHDRAW_DISCRIMINATOR);
+            }
+        }
+        data
+    }
+}
+
+/// The fixed accounts every adapter instruction receives.
+pub struct AdapterAccounts<'info> {
+    pub vault: AccountInfo<'info>,
+    pub vault_token_account: AccountInfo<'info>,
+    pub receipt_token_account: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+}
+
+/// Invoke `yield_program` with the vault PDA as signer and propagate any error.
+///
+/// `extra_accounts` are appended after the fixed accounts; signer accounts are
+/// skipped since they are multisig approvals for the vault instruction itself.
+pub fn invoke_adapter<'info>(
+    yield_program: &AccountInfo<'info>,
+    ix: AdapterInstruction,
+    accounts: AdapterAccounts<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    require!(
+        yield_program.executable,
+        ErrorCode::YieldProgramNotWhitelisted
+    );
+
+    let extras: Vec<&AccountInfo<'info>> =
+        extra_accounts.iter().filter(|ai| !ai.is_signer).collect();
+
+    let mut metas = Vec::with_capacity(4 + extras.len());
+    metas.push(AccountMeta::new_readonly(accounts.vault.key(), true));
+    metas.push(AccountMeta::new(accounts.vault_token_account.key(), false));
+    metas.push(AccountMeta::new(
+        accounts.receipt_token_account.key(),
+        false,
+    ));
+    metas.push(AccountMeta::new_readonly(
+        accounts.token_program.key(),
+        false,
+    ));
+    for ai in extras.iter() {
+        metas.push(AccountMeta {
+            pubkey: ai.key(),
+            is_signer: false,
+            is_writable: ai.is_writable,
+        });
+    }
+
+    let mut infos = Vec::with_capacity(5 + extras.len());
+    infos.push(accounts.vault);
+    infos.push(accounts.vault_token_account);
+    infos.push(accounts.receipt_token_account);
+    infos.push(accounts.token_program);
+    for ai in extras.into_iter() {
+        infos.push(ai.clone());
+    }
+    infos.push(yield_program.clone());
+
+    let instruction = Instruction {
+        program_id: yield_program.key(),
+        accounts: metas,
+        data: ix.data(),
+    };
+    invoke_signed(&instruction, &infos, signer)?;
+    Ok(())
+}
+
+/// Query the vault's position value from `yield_program` via `adapter_position_value`.
+/// The value must be returned by the yield program itself as a little-endian `u64`.
+pub fn query_position_value<'info>(
+    yield_program: &AccountInfo<'info>,
+    accounts: AdapterAccounts<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    signer: &[&[&[u8]]],
+) -> Result<u64> {
+    invoke_adapter(
+        yield_program,
+        AdapterInstruction::PositionValue,
+        accounts,
+        extra_accounts,
+        signer,
+    )?;
+    let (program_id, data) = get_return_data().ok_or(ErrorCode::YieldAdapterMismatch)?;
+    require_keys_eq!(
+        program_id,
+        yield_program.key(),
+        ErrorCode::YieldAdapterMismatch
+    );
+    require!(data.len() >= 8, ErrorCode::YieldAdapterMismatch);
+    Ok(u64::from_le_bytes(data[..8].try_into().unwrap()))
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+
+use collateral_vault::error::ErrorCode as CollateralError;
+use collateral_vault::oracle::{PriceFeed, PRICE_FEED_LEN};
+
+pub const PRICE_FEED_SEED: &[u8] = b"price_feed";
+
+// IMPORTANT: Program id must match Anchor.toml (programs.localnet.mock_oracle)
+declare_id!("4fosjJynNSxi8yxuArcgndxxGJda2NnMpM3DzcVWCPcD");
+
+#[program]
+pub mod mock_oracle {
+    use super::*;
+
+    // Create a raw price account for `mint` (no discriminator, see collateral_vault::oracle)
+    pub fn init_price_feed(
+        ctx: Context<InitPriceFeed>,
+        price: i64,
+        conf: u64,
+        expo: i32,
+    ) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+        let bump = ctx.bumps.price_feed;
+        let signer_seeds: &[&[u8]] = &[PRICE_FEED_SEED, mint_key.as_ref(), &[bump]];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        let lamports = Rent::get()?.minimum_balance(PRICE_FEED_LEN);
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.price_feed.to_account_info(),
+            },
+            signer,
+        );
+        system_program::create_account(cpi_ctx, lamports, PRICE_FEED_LEN as u64, &crate::ID)?;
+
+        write_feed(&ctx.accounts.price_feed, price, conf, expo, None)
+    }
+
+    // Overwrite the feed; publish_time defaults to the current clock so tests can
+    // also simulate stale prices by passing an old timestamp
+    pub fn set_price(
+        ctx: Context<SetPrice>,
+        price: i64,
+        conf: u64,
+        expo: i32,
+        publish_time: Option<i64>,
+    ) -> Result<()> {
+        write_feed(&ctx.accounts.price_feed, price, conf, expo, publish_time)
+    }
+}
+
+fn write_feed(
+    price_feed: &AccountInfo<'_>,
+    price: i64,
+    conf: u64,
+    expo: i32,
+    publish_time: Option<i64>,
+) -> Result<()> {
+    require!(price > 0, CollateralError::InvalidAmount);
+    let publish_time = match publish_time {
+        Some(t) => t,
+        None => Clock::get()?.unix_timestamp,
+    };
+    let mut data = price_feed.try_borrow_mut_data()?;
+    PriceFeed {
+        expo,
+        price,
+        conf,
+        publish_time,
+    }
+    .pack_into(&mut data)
+}
+
+#[derive(Accounts)]
+pub struct InitPriceFeed<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: only used as the seed identifying which mint this feed prices
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: created in the handler as a raw account owned by this program
+    #[account(
+        mut,
+        seeds = [PRICE_FEED_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub price_feed: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPrice<'info> {
+    pub payer: Signer<'info>,
+
+    /// CHECK: only used as the seed identifying which mint this feed prices
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: raw feed account owned by this program
+    #[account(
+        mut,
+        seeds = [PRICE_FEED_SEED, mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub price_feed: UncheckedAccount<'info>,
+}
@@ -2,10 +2,12 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
 use collateral_vault::error::ErrorCode as CollateralError;
-use collateral_vault::state::{CollateralVault as VaultState, VaultAuthority};
-use collateral_vault::types::PositionSummary;
-
-pub const POSITION_SUMMARY_SEED: &[u8] = b"position_summary";
+use collateral_vault::state::{CollateralVault as VaultState, IntegratorRegistry, VaultAuthority};
+use collateral_vault::types::SettlementEntry;
+use position_interface::{
+    PositionSummary, POSITION_SUMMARY_DISCRIMINATOR, POSITION_SUMMARY_SEED,
+    POSITION_SUMMARY_VERSION,
+};
 
 // IMPORTANT: Program id must match Anchor.toml (programs.localnet.mock_position_manager)
 // Updated to match the actual deployed program ID on testnet
@@ -17,11 +19,14 @@ pub mod mock_position_manager {
 
     pub fn init_position_summary(ctx: Context<InitPositionSummary>) -> Result<()> {
         let summary = &mut ctx.accounts.position_summary;
+        summary.version = POSITION_SUMMARY_VERSION;
         summary.vault = ctx.accounts.vault.key();
         summary.owner = ctx.accounts.vault.owner;
         summary.open_positions = 0;
         summary.locked_amount = 0;
+        summary.required_margin = 0;
         summary.last_updated_slot = Clock::get()?.slot;
+        summary.bump = ctx.bumps.position_summary;
         Ok(())
     }
 
@@ -39,17 +44,20 @@ pub mod mock_position_manager {
             .locked_amount
             .checked_add(amount)
             .ok_or(CollateralError::Overflow)?;
+        // Mock margin model: every locked unit is required margin
+        summary.required_margin = summary.locked_amount;
         summary.last_updated_slot = Clock::get()?.slot;
 
         let cpi_program = ctx.accounts.collateral_vault_program.to_account_info();
         let cpi_accounts = collateral_vault::cpi::accounts::LockCollateral {
             caller_program: ctx.accounts.caller_program.to_account_info(),
             vault_authority: ctx.accounts.vault_authority.to_account_info(),
+            integrator: ctx.accounts.integrator.to_account_info(),
             instructions: ctx.accounts.instructions.to_account_info(),
             vault: ctx.accounts.vault.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        collateral_vault::cpi::lock_collateral(cpi_ctx, amount)
+        collateral_vault::cpi::lock_collateral(cpi_ctx, amount, None)
     }
 
     // Close a mock position by unlocking collateral via CPI into collateral_vault
@@ -67,12 +75,14 @@ pub mod mock_position_manager {
             .locked_amount
             .checked_sub(amount)
             .ok_or(CollateralError::Overflow)?;
+        summary.required_margin = summary.locked_amount;
         summary.last_updated_slot = Clock::get()?.slot;
 
         let cpi_program = ctx.accounts.collateral_vault_program.to_account_info();
         let cpi_accounts = collateral_vault::cpi::accounts::UnlockCollateral {
             caller_program: ctx.accounts.caller_program.to_account_info(),
             vault_authority: ctx.accounts.vault_authority.to_account_info(),
+            integrator: ctx.accounts.integrator.to_account_info(),
             instructions: ctx.accounts.instructions.to_account_info(),
             vault: ctx.accounts.vault.to_account_info(),
         };
@@ -80,6 +90,20 @@ pub mod mock_position_manager {
         collateral_vault::cpi::unlock_collateral(cpi_ctx, amount)
     }
 
+    // Keep the lease on this program's locks in the vault alive
+    pub fn renew_lease(ctx: Context<RenewLease>, lease_seconds: i64) -> Result<()> {
+        let cpi_program = ctx.accounts.collateral_vault_program.to_account_info();
+        let cpi_accounts = collateral_vault::cpi::accounts::RenewLock {
+            caller_program: ctx.accounts.caller_program.to_account_info(),
+            vault_authority: ctx.accounts.vault_authority.to_account_info(),
+            integrator: ctx.accounts.integrator.to_account_info(),
+            instructions: ctx.accounts.instructions.to_account_info(),
+            vault: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        collateral_vault::cpi::renew_lock(cpi_ctx, lease_seconds)
+    }
+
     pub fn rebalance_collateral(ctx: Context<RebalanceCollateral>, amount: u64) -> Result<()> {
         require!(amount > 0, CollateralError::InvalidAmount);
 
@@ -87,16 +111,38 @@ pub mod mock_position_manager {
         let cpi_accounts = collateral_vault::cpi::accounts::TransferCollateral {
             caller_program: ctx.accounts.caller_program.to_account_info(),
             vault_authority: ctx.accounts.vault_authority.to_account_info(),
+            integrator: ctx.accounts.integrator.to_account_info(),
             instructions: ctx.accounts.instructions.to_account_info(),
             from_vault: ctx.accounts.from_vault.to_account_info(),
             to_vault: ctx.accounts.to_vault.to_account_info(),
             from_vault_token_account: ctx.accounts.from_vault_token_account.to_account_info(),
             to_vault_token_account: ctx.accounts.to_vault_token_account.to_account_info(),
+            treasury_token_account: ctx.accounts.treasury_token_account.to_account_info(),
             token_program: ctx.accounts.token_program.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         collateral_vault::cpi::transfer_collateral(cpi_ctx, amount)
     }
+
+    // Settle several vaults in one CPI; remaining accounts are forwarded as
+    // [vault, vault_token_account] pairs in entry order
+    pub fn settle_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>,
+        entries: Vec<SettlementEntry>,
+    ) -> Result<()> {
+        let cpi_program = ctx.accounts.collateral_vault_program.to_account_info();
+        let cpi_accounts = collateral_vault::cpi::accounts::BatchSettle {
+            caller_program: ctx.accounts.caller_program.to_account_info(),
+            vault_authority: ctx.accounts.vault_authority.to_account_info(),
+            integrator: ctx.accounts.integrator.to_account_info(),
+            instructions: ctx.accounts.instructions.to_account_info(),
+            treasury_token_account: ctx.accounts.treasury_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        collateral_vault::cpi::batch_settle(cpi_ctx, entries)
+    }
 }
 
 #[derive(Accounts)]
@@ -127,6 +173,10 @@ pub struct OpenPosition<'info> {
     /// Vault authority of the downstream program
     pub vault_authority: Account<'info, VaultAuthority>,
 
+    /// Registry record of this program on the downstream program
+    #[account(mut)]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
     /// CHECK: address constraint pins this to the instructions sysvar PDA
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions: AccountInfo<'info>,
@@ -154,6 +204,10 @@ pub struct ClosePosition<'info> {
     /// Vault authority of the downstream program
     pub vault_authority: Account<'info, VaultAuthority>,
 
+    /// Registry record of this program on the downstream program
+    #[account(mut)]
+    pub integrator: Account<'info, IntegratorRegistry>,
+
     /// CHECK: address constraint pins this to the instructions sysvar PDA
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions: AccountInfo<'info>,
@@ -173,13 +227,40 @@ pub struct ClosePosition<'info> {
     pub collateral_vault_program: Program<'info, collateral_vault::program::CollateralVault>,
 }
 
+#[derive(Accounts)]
+pub struct RenewLease<'info> {
+    /// CHECK: passed as an Unchecked account to be compared as the caller id on the downstream program
+    pub caller_program: UncheckedAccount<'info>,
+
+    /// Vault authority of the downstream program
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// Registry record of this program on the downstream program
+    pub integrator: Account<'info, IntegratorRegistry>,
+
+    /// CHECK: address constraint pins this to the instructions sysvar PDA
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Vault whose lease is renewed
+    #[account(mut)]
+    pub vault: Account<'info, VaultState>,
+
+    /// The downstream program we are CPI-ing into
+    pub collateral_vault_program: Program<'info, collateral_vault::program::CollateralVault>,
+}
+
 #[derive(Accounts)]
 pub struct RebalanceCollateral<'info> {
     /// CHECK: passed as an Unchecked account to be compared as the caller id on the downstream program
     pub caller_program: UncheckedAccount<'info>,
 
+    #[account(mut)]
     pub vault_authority: Account<'info, VaultAuthority>,
 
+    /// Registry record of this program on the downstream program
+    pub integrator: Account<'info, IntegratorRegistry>,
+
     /// CHECK: address constraint pins this to the instructions sysvar PDA
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions: AccountInfo<'info>,
@@ -196,18 +277,50 @@ pub struct RebalanceCollateral<'info> {
     #[account(mut)]
     pub to_vault_token_account: Account<'info, TokenAccount>,
 
+    /// Collateral-vault treasury for the mint; receives the transfer fee
+    #[account(mut)]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
     pub token_program: Program<'info, Token>,
 
     pub collateral_vault_program: Program<'info, collateral_vault::program::CollateralVault>,
 }
 
-#[account]
+#[derive(Accounts)]
+pub struct SettleBatch<'info> {
+    /// CHECK: passed as an Unchecked account to be compared as the caller id on the downstream program
+    pub caller_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// Registry record of this program on the downstream program
+    pub integrator: Account<'info, IntegratorRegistry>,
+
+    /// CHECK: address constraint pins this to the instructions sysvar PDA
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Collateral-vault treasury for the mint; receives the settlement fees
+    #[account(mut)]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub collateral_vault_program: Program<'info, collateral_vault::program::CollateralVault>,
+}
+
+// Same layout and discriminator as position_interface::PositionSummary
+#[account(discriminator = &POSITION_SUMMARY_DISCRIMINATOR)]
 pub struct PositionSummaryAccount {
+    pub version: u8,
     pub vault: Pubkey,
     pub owner: Pubkey,
     pub open_positions: u64,
     pub locked_amount: u64,
+    pub required_margin: u64,
     pub last_updated_slot: u64,
+    pub bump: u8,
 }
 
 impl PositionSummaryAccount {
@@ -219,3 +332,30 @@ impl PositionSummaryAccount {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_account_matches_position_interface() {
+        let account = PositionSummaryAccount {
+            version: POSITION_SUMMARY_VERSION,
+            vault: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            open_positions: 1,
+            locked_amount: 500,
+            required_margin: 500,
+            last_updated_slot: 7,
+            bump: 254,
+        };
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 8 + PositionSummaryAccount::SIZE);
+
+        let summary = PositionSummary::decode(&data).unwrap();
+        assert_eq!(summary.vault, account.vault);
+        assert_eq!(summary.required_margin, 500);
+        assert_eq!(summary.bump, 254);
+    }
+}
@@ -0,0 +1,423 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+
+use collateral_vault::error::ErrorCode as CollateralError;
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const POOL_TOKENS_SEED: &[u8] = b"pool_tokens";
+pub const RECEIPT_MINT_SEED: &[u8] = b"receipt_mint";
+pub const REDEMPTION_QUEUE_SEED: &[u8] = b"redemption_queue";
+
+// Receipt value is tracked as an index: collateral per receipt, scaled by INDEX_SCALE
+pub const INDEX_SCALE: u64 = 1_000_000_000;
+
+// IMPORTANT: Program id must match Anchor.toml (programs.localnet.mock_yield_program)
+declare_id!("Aoid8iX717JYFakzLTWx2LVjvvApfvXPq7SmQ6JfeJou");
+
+// Implements the collateral_vault::yield_adapter interface. Every adapter_*
+// instruction expects the pool accounts as strategy-specific extras, in order:
+// pool, pool_token_account, receipt_mint (all writable), then redemption_queue
+// for adapter_request_withdraw / adapter_claim_withdraw.
+//
+// Accrued yield is paid from the pool's own token balance: tests fund it by
+// transferring tokens to the pool token account. Payouts are capped by that
+// balance, so an underfunded pool also exercises the vault's loss paths.
+#[program]
+pub mod mock_yield_program {
+    use super::*;
+
+    // Create a pool for `mint` with its token account and receipt mint
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        rate_per_slot: u64,
+        async_redemptions: bool,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.mint = ctx.accounts.mint.key();
+        pool.receipt_mint = ctx.accounts.receipt_mint.key();
+        pool.pool_token_account = ctx.accounts.pool_token_account.key();
+        pool.rate_per_slot = rate_per_slot;
+        pool.index = INDEX_SCALE;
+        pool.last_accrual_slot = Clock::get()?.slot;
+        pool.async_redemptions = async_redemptions;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    // Change the accrual rate (index increase per slot) and redemption mode
+    pub fn set_pool_config(
+        ctx: Context<SetPoolConfig>,
+        rate_per_slot: u64,
+        async_redemptions: bool,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.accrue(Clock::get()?.slot);
+        pool.rate_per_slot = rate_per_slot;
+        pool.async_redemptions = async_redemptions;
+        Ok(())
+    }
+
+    // Create the per-vault queue used by async redemptions (the vault PDA cannot pay rent)
+    pub fn init_redemption_queue(ctx: Context<InitRedemptionQueue>) -> Result<()> {
+        let queue = &mut ctx.accounts.redemption_queue;
+        queue.pool = ctx.accounts.pool.key();
+        queue.vault = ctx.accounts.vault.key();
+        queue.owed = 0;
+        queue.bump = ctx.bumps.redemption_queue;
+        Ok(())
+    }
+
+    // Pull `amount` from the vault token account and mint receipts at the current index
+    pub fn adapter_deposit(ctx: Context<Adapter>, amount: u64) -> Result<()> {
+        require!(amount > 0, CollateralError::InvalidAmount);
+        let pool = &mut ctx.accounts.pool;
+        pool.accrue(Clock::get()?.slot);
+        let receipts = receipts_for(amount, pool.index)?;
+        require!(receipts > 0, CollateralError::InvalidAmount);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let mint_key = ctx.accounts.pool.mint;
+        let signer_seeds: &[&[u8]] = &[POOL_SEED, mint_key.as_ref(), &[ctx.accounts.pool.bump]];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.receipt_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer,
+            ),
+            receipts,
+        )
+    }
+
+    // Burn receipts worth `amount` and pay it back (capped by the pool balance)
+    pub fn adapter_withdraw(ctx: Context<Adapter>, amount: u64) -> Result<()> {
+        let value = burn_receipts(ctx.accounts, amount)?;
+        pay_out(ctx.accounts, value)?;
+        Ok(())
+    }
+
+    // Rewards are reinvested by construction (they raise the index), so harvesting
+    // only checkpoints the accrual
+    pub fn adapter_harvest(ctx: Context<Adapter>) -> Result<()> {
+        ctx.accounts.pool.accrue(Clock::get()?.slot);
+        Ok(())
+    }
+
+    // View: value of the vault's receipts at the current slot, as return data
+    pub fn adapter_position_value(ctx: Context<Adapter>) -> Result<u64> {
+        let index = ctx.accounts.pool.index_at(Clock::get()?.slot);
+        value_of(ctx.accounts.receipt_token_account.amount, index)
+    }
+
+    // Burn receipts worth `amount`; sync pools pay at once, async pools queue it
+    pub fn adapter_request_withdraw(ctx: Context<AdapterRedemption>, amount: u64) -> Result<()> {
+        let value = burn_receipts(&mut ctx.accounts.adapter, amount)?;
+        if ctx.accounts.adapter.pool.async_redemptions {
+            let queue = &mut ctx.accounts.redemption_queue;
+            queue.owed = queue
+                .owed
+                .checked_add(value)
+                .ok_or(CollateralError::Overflow)?;
+        } else {
+            pay_out(&ctx.accounts.adapter, value)?;
+        }
+        Ok(())
+    }
+
+    // Pay as much of the queued redemption as the pool balance allows
+    pub fn adapter_claim_withdraw(ctx: Context<AdapterRedemption>) -> Result<()> {
+        let paid = pay_out(&ctx.accounts.adapter, ctx.accounts.redemption_queue.owed)?;
+        let queue = &mut ctx.accounts.redemption_queue;
+        queue.owed = queue
+            .owed
+            .checked_sub(paid)
+            .ok_or(CollateralError::Overflow)?;
+        Ok(())
+    }
+}
+
+// Burn the receipts backing `amount` (rounded up, capped by the vault's holding)
+// and return their value, never more than `amount`
+fn burn_receipts(accounts: &mut Adapter<'_>, amount: u64) -> Result<u64> {
+    require!(amount > 0, CollateralError::InvalidAmount);
+    let pool = &mut accounts.pool;
+    pool.accrue(Clock::get()?.slot);
+    let receipts =
+        receipts_for_ceil(amount, pool.index)?.min(accounts.receipt_token_account.amount);
+    require!(receipts > 0, CollateralError::InvalidAmount);
+    let value = value_of(receipts, pool.index)?.min(amount);
+
+    token::burn(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint: accounts.receipt_mint.to_account_info(),
+                from: accounts.receipt_token_account.to_account_info(),
+                authority: accounts.vault.to_account_info(),
+            },
+        ),
+        receipts,
+    )?;
+    Ok(value)
+}
+
+// Transfer up to `amount` from the pool to the vault token account; returns the amount paid
+fn pay_out(accounts: &Adapter<'_>, amount: u64) -> Result<u64> {
+    let paid = amount.min(accounts.pool_token_account.amount);
+    if paid == 0 {
+        return Ok(0);
+    }
+    let mint_key = accounts.pool.mint;
+    let signer_seeds: &[&[u8]] = &[POOL_SEED, mint_key.as_ref(), &[accounts.pool.bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+    token::transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.pool_token_account.to_account_info(),
+                to: accounts.vault_token_account.to_account_info(),
+                authority: accounts.pool.to_account_info(),
+            },
+            signer,
+        ),
+        paid,
+    )?;
+    Ok(paid)
+}
+
+fn receipts_for(amount: u64, index: u64) -> Result<u64> {
+    let receipts = (amount as u128) * (INDEX_SCALE as u128) / (index as u128);
+    u64::try_from(receipts).map_err(|_| error!(CollateralError::Overflow))
+}
+
+fn receipts_for_ceil(amount: u64, index: u64) -> Result<u64> {
+    let receipts = ((amount as u128) * (INDEX_SCALE as u128)).div_ceil(index as u128);
+    u64::try_from(receipts).map_err(|_| error!(CollateralError::Overflow))
+}
+
+fn value_of(receipts: u64, index: u64) -> Result<u64> {
+    let value = (receipts as u128) * (index as u128) / (INDEX_SCALE as u128);
+    u64::try_from(value).map_err(|_| error!(CollateralError::Overflow))
+}
+
+#[account]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub pool_token_account: Pubkey,
+    pub rate_per_slot: u64,
+    pub index: u64,
+    pub last_accrual_slot: u64,
+    pub async_redemptions: bool,
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const LEN: usize = 8 + 32 * 4 + 8 * 3 + 1 + 1;
+
+    // Linear accrual: the index grows by `rate_per_slot` every slot
+    pub fn index_at(&self, slot: u64) -> u64 {
+        let elapsed = slot.saturating_sub(self.last_accrual_slot);
+        self.index
+            .saturating_add(self.rate_per_slot.saturating_mul(elapsed))
+    }
+
+    pub fn accrue(&mut self, slot: u64) {
+        self.index = self.index_at(slot);
+        self.last_accrual_slot = slot;
+    }
+}
+
+#[account]
+pub struct RedemptionQueue {
+    pub pool: Pubkey,
+    pub vault: Pubkey,
+    pub owed: u64,
+    pub bump: u8,
+}
+
+impl RedemptionQueue {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Pool::LEN,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [POOL_TOKENS_SEED, mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [RECEIPT_MINT_SEED, mint.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = pool,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = admin @ CollateralError::Unauthorized,
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct InitRedemptionQueue<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: only used as the seed identifying the depositing vault
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(seeds = [POOL_SEED, pool.mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RedemptionQueue::LEN,
+        seeds = [REDEMPTION_QUEUE_SEED, pool.key().as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Account order follows collateral_vault::yield_adapter: the four fixed accounts, then extras
+#[derive(Accounts)]
+pub struct Adapter<'info> {
+    /// Vault PDA, signed by the collateral-vault program
+    pub vault: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.owner == vault.key() @ CollateralError::Unauthorized,
+        constraint = vault_token_account.mint == pool.mint @ CollateralError::Unauthorized,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = receipt_token_account.owner == vault.key() @ CollateralError::Unauthorized,
+        constraint = receipt_token_account.mint == pool.receipt_mint @ CollateralError::Unauthorized,
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(mut, seeds = [POOL_SEED, pool.mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut, address = pool.pool_token_account)]
+    pub pool_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut, address = pool.receipt_mint)]
+    pub receipt_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct AdapterRedemption<'info> {
+    pub adapter: Adapter<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_QUEUE_SEED, adapter.pool.key().as_ref(), adapter.vault.key().as_ref()],
+        bump = redemption_queue.bump,
+    )]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+    use collateral_vault::yield_adapter::*;
+
+    #[test]
+    fn adapter_discriminators_match_vault_interface() {
+        assert_eq!(
+            instruction::AdapterDeposit::DISCRIMINATOR,
+            ADAPTER_DEPOSIT_DISCRIMINATOR
+        );
+        assert_eq!(
+            instruction::AdapterWithdraw::DISCRIMINATOR,
+            ADAPTER_WITHDRAW_DISCRIMINATOR
+        );
+        assert_eq!(
+            instruction::AdapterHarvest::DISCRIMINATOR,
+            ADAPTER_HARVEST_DISCRIMINATOR
+        );
+        assert_eq!(
+            instruction::AdapterPositionValue::DISCRIMINATOR,
+            ADAPTER_POSITION_VALUE_DISCRIMINATOR
+        );
+        assert_eq!(
+            instruction::AdapterRequestWithdraw::DISCRIMINATOR,
+            ADAPTER_REQUEST_WITHDRAW_DISCRIMINATOR
+        );
+        assert_eq!(
+            instruction::AdapterClaimWithdraw::DISCRIMINATOR,
+            ADAPTER_CLAIM_WITHDRAW_DISCRIMINATOR
+        );
+    }
+
+    #[test]
+    fn receipts_round_trip_at_index() {
+        let index = INDEX_SCALE + INDEX_SCALE / 10; // 1.1 collateral per receipt
+        let receipts = receipts_for(1_100, index).unwrap();
+        assert_eq!(receipts, 1_000);
+        assert_eq!(value_of(receipts, index).unwrap(), 1_100);
+        // Burning for a withdrawal rounds up so the vault never gets more than it burns
+        assert_eq!(receipts_for_ceil(1_101, index).unwrap(), 1_001);
+    }
+}
@@ -0,0 +1,217 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, web3, BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddress,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { CollateralVault } from "../target/types/collateral_vault";
+import { MockYieldProgram } from "../target/types/mock_yield_program";
+
+describe("yield: deposit → harvest → withdraw against mock-yield-program", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.collateralVault as Program<CollateralVault>;
+  const yieldProgram = anchor.workspace
+    .mockYieldProgram as Program<MockYieldProgram>;
+
+  it("books principal, harvested gains and withdrawals through the adapter", async () => {
+    const user = provider.wallet as anchor.Wallet;
+    const payer = (user as any).payer;
+    const connection = provider.connection;
+
+    const owner = web3.Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(owner.publicKey, web3.LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+
+    const usdtMint = await createMint(connection, payer, user.publicKey, null, 6);
+    const ownerAta = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      usdtMint,
+      owner.publicKey
+    );
+    await mintTo(connection, payer, usdtMint, ownerAta.address, user.publicKey, 1_000_000n);
+
+    const [vaultPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const vaultAta = await getAssociatedTokenAddress(usdtMint, vaultPda, true);
+    const [vaPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_authority")],
+      program.programId
+    );
+
+    await program.methods
+      .initializeVault()
+      .accountsPartial({
+        user: owner.publicKey,
+        vault: vaultPda,
+        vaultTokenAccount: vaultAta,
+        usdtMint,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([owner])
+      .rpc();
+
+    try {
+      await program.methods
+        .initializeVaultAuthority(false)
+        .accountsPartial({
+          governance: user.publicKey,
+          vaultAuthority: vaPda,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+    } catch (_) {
+      // already initialized by another spec
+    }
+
+    // Deposits and withdrawals pay fees into the treasury for the mint
+    const [treasury] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), usdtMint.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .initializeTreasury()
+      .accountsPartial({
+        governance: user.publicKey,
+        vaultAuthority: vaPda,
+        mint: usdtMint,
+        treasuryTokenAccount: treasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .deposit(new BN(1_000_000))
+      .accountsPartial({
+        authority: owner.publicKey,
+        owner: owner.publicKey,
+        vault: vaultPda,
+        vaultAuthority: vaPda,
+        userTokenAccount: ownerAta.address,
+        vaultTokenAccount: vaultAta,
+        treasuryTokenAccount: treasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    // Whitelist the mock yield program
+    const va = await program.account.vaultAuthority.fetch(vaPda);
+    const whitelisted = (va.yieldWhitelist as web3.PublicKey[]).some((p) =>
+      p.equals(yieldProgram.programId)
+    );
+    if (!whitelisted) {
+      await program.methods
+        .addYieldProgram(yieldProgram.programId)
+        .accountsPartial({ governance: user.publicKey, vaultAuthority: vaPda })
+        .rpc();
+    }
+
+    // Pool accruing 0.01% of the index per slot
+    const [poolPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), usdtMint.toBuffer()],
+      yieldProgram.programId
+    );
+    const [poolTokens] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_tokens"), usdtMint.toBuffer()],
+      yieldProgram.programId
+    );
+    const [receiptMint] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), usdtMint.toBuffer()],
+      yieldProgram.programId
+    );
+    await yieldProgram.methods
+      .initializePool(new BN(100_000), false)
+      .accountsPartial({
+        admin: user.publicKey,
+        mint: usdtMint,
+        pool: poolPda,
+        poolTokenAccount: poolTokens,
+        receiptMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const receiptAta = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      receiptMint,
+      vaultPda,
+      true
+    );
+    const extras = [poolPda, poolTokens, receiptMint].map((pubkey) => ({
+      pubkey,
+      isSigner: false,
+      isWritable: true,
+    }));
+    const yieldAccounts = {
+      owner: owner.publicKey,
+      vault: vaultPda,
+      vaultAuthority: vaPda,
+      yieldProgram: yieldProgram.programId,
+      vaultTokenAccount: vaultAta,
+      receiptTokenAccount: receiptAta.address,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    await program.methods
+      .yieldDeposit(new BN(400_000))
+      .accountsPartial({ authority: owner.publicKey, ...yieldAccounts })
+      .remainingAccounts(extras)
+      .signers([owner])
+      .rpc();
+
+    let vault = await program.account.collateralVault.fetch(vaultPda);
+    expect(vault.yieldDepositedBalance.toNumber()).to.eq(400_000);
+    expect(vault.availableBalance.toNumber()).to.eq(600_000);
+    expect(Number((await getAccount(connection, poolTokens)).amount)).to.eq(400_000);
+
+    // Back the accruing yield, let a few slots pass and mark the position
+    await mintTo(connection, payer, usdtMint, poolTokens, user.publicKey, 100_000n);
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    await program.methods
+      .harvestYield()
+      .accountsPartial({
+        vault: vaultPda,
+        vaultAuthority: vaPda,
+        yieldProgram: yieldProgram.programId,
+        vaultTokenAccount: vaultAta,
+        receiptTokenAccount: receiptAta.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(extras)
+      .rpc();
+
+    vault = await program.account.collateralVault.fetch(vaultPda);
+    expect(vault.yieldAccruedBalance.toNumber()).to.be.greaterThan(0);
+
+    // Withdrawing the principal leaves receipts for the accrued gain
+    await program.methods
+      .yieldWithdraw(new BN(400_000))
+      .accountsPartial({ authority: owner.publicKey, ...yieldAccounts })
+      .remainingAccounts(extras)
+      .signers([owner])
+      .rpc();
+
+    vault = await program.account.collateralVault.fetch(vaultPda);
+    expect(vault.yieldDepositedBalance.toNumber()).to.eq(0);
+    expect(vault.availableBalance.toNumber()).to.eq(1_000_000);
+    expect(Number((await getAccount(connection, receiptAta.address)).amount)).to.be.greaterThan(0);
+  });
+});